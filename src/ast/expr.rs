@@ -1,4 +1,4 @@
-use crate::token::{Literal, Token};
+use crate::{ast::stmt::FunDecl, token::{Literal, Token}};
 
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub enum Expr {
@@ -16,8 +16,11 @@ pub enum Expr {
     This(This),
     Super(Super),
     Array(ArrayExpr),
+    Map(MapExpr),
     SubscriptGet(SubscriptGetExpr),
-    SubscriptSet(SubscriptSetExpr)
+    SubscriptSet(SubscriptSetExpr),
+    Lambda(LambdaExpr),
+    Interpolation(InterpolationExpr)
 }
 
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -48,10 +51,15 @@ pub struct Identifier {
     pub name: Token
 }
 
+/// `name = value`. For compound assignments `op` is the operator, such as `+=`, and the variable
+/// is combined with `value`; `++` and `--` are parsed with a value of `1`. A `postfix` increment or
+/// decrement evaluates to the variable's old value.
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct AssignExpr {
     pub name: Token,
-    pub value: Box<Expr>
+    pub value: Box<Expr>,
+    pub op: Option<Token>,
+    pub postfix: bool
 }
 
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -74,11 +82,14 @@ pub struct GetExpr {
     pub name: Token
 }
 
+/// `object.name = value`; `op` and `postfix` are as in [`AssignExpr`].
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct SetExpr {
     pub object: Box<Expr>,
     pub name: Token,
-    pub value: Box<Expr>
+    pub value: Box<Expr>,
+    pub op: Option<Token>,
+    pub postfix: bool
 }
 
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -104,6 +115,13 @@ pub struct ArrayExpr {
     pub elements: Vec<Expr>
 }
 
+/// `{key: value, ...}`; `brace` locates errors about keys that cannot be hashed.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct MapExpr {
+    pub brace: Token,
+    pub entries: Vec<(Expr, Expr)>
+}
+
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct SubscriptGetExpr {
     pub array: Box<Expr>,
@@ -111,10 +129,27 @@ pub struct SubscriptGetExpr {
     pub bracket: Token
 }
 
+/// `array[index] = value`; `op` and `postfix` are as in [`AssignExpr`].
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct SubscriptSetExpr {
     pub array: Box<Expr>,
     pub index: Box<Expr>,
     pub value: Box<Expr>,
-    pub bracket: Token
-}
\ No newline at end of file
+    pub bracket: Token,
+    pub op: Option<Token>,
+    pub postfix: bool
+}
+
+/// `fun (a, b) { ... }` or `(a, b) => expr`, which is parsed as a body of `return expr;`.
+/// The name of `decl` is the `fun` or `=>` token, renamed to `anonymous`.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct LambdaExpr {
+    pub decl: FunDecl
+}
+
+/// `"Hello ${name}!"`, whose `parts` are the string literals and embedded expressions in order.
+/// The value is their concatenation, with each part converted to a string the way `print` would.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct InterpolationExpr {
+    pub parts: Vec<Expr>
+}
@@ -1,2 +1,3 @@
 pub mod expr;
 pub mod stmt;
+pub mod printer;
@@ -0,0 +1,260 @@
+use crate::ast::{expr::*, stmt::*};
+use crate::token::{Literal, Token};
+use crate::visit::Visitor;
+
+/// Pretty-prints a syntax tree as indented S-expressions, one top-level statement per line.
+pub struct AstPrinter {}
+
+impl AstPrinter {
+    pub fn print(stmts: &[Stmt]) -> String {
+        let mut printer = Self {};
+        stmts.iter()
+            .map(|stmt| printer.stmt(stmt))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> String {
+        self.visit_stmt(stmt).ok().flatten().unwrap_or_default()
+    }
+
+    fn expr(&mut self, expr: &Expr) -> String {
+        self.visit_expr(expr).ok().flatten().unwrap_or_default()
+    }
+
+    fn body(&mut self, stmts: &[Stmt]) -> String {
+        stmts.iter().map(|stmt| format!("\n{}", indent(&self.stmt(stmt)))).collect()
+    }
+
+    fn lambda(&mut self, fun_decl: &FunDecl) -> String {
+        let params: Vec<&str> = fun_decl.params.iter().map(|param| param.text.as_str()).collect();
+        format!("(lambda ({}){})", params.join(" "), self.body(&fun_decl.body))
+    }
+
+    fn function(&mut self, fun_decl: &FunDecl) -> String {
+        let params: Vec<&str> = fun_decl.params.iter().map(|param| param.text.as_str()).collect();
+        format!("(fun {} ({}){})", fun_decl.name.text, params.join(" "), self.body(&fun_decl.body))
+    }
+
+    /// `=`, the compound operator, or `post++` and `post--` for postfix increments and decrements.
+    fn assignment_op(&self, op: &Option<Token>, postfix: bool) -> String {
+        match op {
+            Some(op) if postfix => format!("post{}", op.text),
+            Some(op) => op.text.clone(),
+            None => "=".to_string()
+        }
+    }
+
+    fn parenthesize(&mut self, name: &str, exprs: &[&Expr]) -> String {
+        let mut result = format!("({name}");
+        for expr in exprs {
+            result.push(' ');
+            result.push_str(&self.expr(expr));
+        }
+        result.push(')');
+        result
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("  {line}")).collect::<Vec<String>>().join("\n")
+}
+
+impl Visitor for AstPrinter {
+    type R = String;
+    type E = ();
+
+    fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("expr", &[&expr_stmt.expr])))
+    }
+
+    fn visit_print_stmt(&mut self, print_stmt: &PrintStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("print", &[&print_stmt.expr])))
+    }
+
+    fn visit_if_stmt(&mut self, if_stmt: &IfStmt) -> Result<Option<Self::R>, Self::E> {
+        let mut result = format!("(if {}\n{}", self.expr(&if_stmt.condition), indent(&self.stmt(&if_stmt.then_stmt)));
+        if let Some(else_stmt) = &if_stmt.else_stmt {
+            result.push_str(&format!("\n{}", indent(&self.stmt(else_stmt))));
+        }
+        result.push(')');
+        Ok(Some(result))
+    }
+
+    fn visit_while_stmt(&mut self, while_stmt: &WhileStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(while {}\n{})", self.expr(&while_stmt.condition), indent(&self.stmt(&while_stmt.stmt)))))
+    }
+
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Self::R>, Self::E> {
+        let iterable = self.expr(&for_in_stmt.iterable);
+        Ok(Some(format!("(for-in {} {iterable}\n{})", for_in_stmt.name.text, indent(&self.stmt(&for_in_stmt.body)))))
+    }
+
+    fn visit_break_stmt(&mut self) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some("(break)".to_string()))
+    }
+
+    fn visit_continue_stmt(&mut self) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some("(continue)".to_string()))
+    }
+
+    fn visit_return_stmt(&mut self, return_stmt: &ReturnStmt) -> Result<Option<Self::R>, Self::E> {
+        if let Some(value) = &return_stmt.value {
+            return Ok(Some(self.parenthesize("return", &[value])));
+        }
+        Ok(Some("(return)".to_string()))
+    }
+
+    fn visit_block(&mut self, block: &Block) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(block{})", self.body(&block.stmts))))
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl) -> Result<Option<Self::R>, Self::E> {
+        if let Some(initializer) = &var_decl.initializer {
+            return Ok(Some(self.parenthesize(&format!("var {}", var_decl.name.text), &[initializer])));
+        }
+        Ok(Some(format!("(var {})", var_decl.name.text)))
+    }
+
+    fn visit_fun_decl(&mut self, fun_decl: &FunDecl) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.function(fun_decl)))
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) -> Result<Option<Self::R>, Self::E> {
+        let mut result = format!("(class {}", class_decl.name.text);
+        if let Some(superclass) = &class_decl.superclass {
+            result.push_str(&format!(" < {}", superclass.name.text));
+        }
+        for method in &class_decl.methods {
+            result.push_str(&format!("\n{}", indent(&self.function(method))));
+        }
+        result.push(')');
+        Ok(Some(result))
+    }
+
+    fn visit_import_stmt(&mut self, import_stmt: &ImportStmt) -> Result<Option<Self::R>, Self::E> {
+        if let Some(names) = &import_stmt.names {
+            let names: Vec<&str> = names.iter().map(|name| name.text.as_str()).collect();
+            return Ok(Some(format!("(import ({}) {})", names.join(" "), import_stmt.path.text)));
+        }
+        Ok(Some(format!("(import {})", import_stmt.path.text)))
+    }
+
+    fn visit_export_stmt(&mut self, export_stmt: &ExportStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(export {})", self.stmt(&export_stmt.decl))))
+    }
+
+    fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("throw", &[&throw_stmt.value])))
+    }
+
+    fn visit_try_stmt(&mut self, try_stmt: &TryStmt) -> Result<Option<Self::R>, Self::E> {
+        let mut result = format!("(try{}", self.body(&try_stmt.body));
+        if let Some(catch_clause) = &try_stmt.catch_clause {
+            let catch = format!("(catch {}{})", catch_clause.name.text, self.body(&catch_clause.body));
+            result.push_str(&format!("\n{}", indent(&catch)));
+        }
+        if let Some(finally_body) = &try_stmt.finally_body {
+            let finally = format!("(finally{})", self.body(finally_body));
+            result.push_str(&format!("\n{}", indent(&finally)));
+        }
+        result.push(')');
+        Ok(Some(result))
+    }
+
+    fn visit_error_stmt(&mut self, error_stmt: &ErrorStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(error at '{}')", error_stmt.token.text)))
+    }
+
+    fn visit_binary_expr(&mut self, binary_expr: &BinaryExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize(&binary_expr.op.text, &[&binary_expr.lhs, &binary_expr.rhs])))
+    }
+
+    fn visit_logical_expr(&mut self, logical_expr: &LogicalExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize(&logical_expr.operator.text, &[&logical_expr.lhs, &logical_expr.rhs])))
+    }
+
+    fn visit_unary_expr(&mut self, unary_expr: &UnaryExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize(&unary_expr.op.text, &[&unary_expr.expr])))
+    }
+
+    fn visit_literal_expr(&mut self, literal_expr: &LiteralExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(match &literal_expr.content {
+            Literal::Bool(value) => value.to_string(),
+            Literal::String(value) => format!("{value:?}"),
+            Literal::Number(value) => value.clone(),
+            Literal::Nil => "nil".to_string()
+        }))
+    }
+
+    fn visit_grouping_expr(&mut self, grouping_expr: &GroupingExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("group", &[&grouping_expr.expr])))
+    }
+
+    fn visit_identifier(&mut self, identifier: &Identifier) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(identifier.name.text.clone()))
+    }
+
+    fn visit_assign_expr(&mut self, assign_expr: &AssignExpr) -> Result<Option<Self::R>, Self::E> {
+        let op = self.assignment_op(&assign_expr.op, assign_expr.postfix);
+        Ok(Some(self.parenthesize(&format!("{op} {}", assign_expr.name.text), &[&assign_expr.value])))
+    }
+
+    fn visit_ternary_expr(&mut self, ternary_expr: &TernaryExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("?", &[&ternary_expr.condition, &ternary_expr.then_expr, &ternary_expr.else_expr])))
+    }
+
+    fn visit_call_expr(&mut self, call_expr: &CallExpr) -> Result<Option<Self::R>, Self::E> {
+        let mut exprs: Vec<&Expr> = vec![&call_expr.name];
+        exprs.extend(call_expr.args.iter());
+        Ok(Some(self.parenthesize("call", &exprs)))
+    }
+
+    fn visit_get_expr(&mut self, get_expr: &GetExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(. {} {})", self.expr(&get_expr.object), get_expr.name.text)))
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &SetExpr) -> Result<Option<Self::R>, Self::E> {
+        let op = self.assignment_op(&set_expr.op, set_expr.postfix);
+        Ok(Some(format!("({op} (. {} {}) {})", self.expr(&set_expr.object), set_expr.name.text, self.expr(&set_expr.value))))
+    }
+
+    fn visit_this(&mut self, _this: &This) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some("this".to_string()))
+    }
+
+    fn visit_super(&mut self, super_expr: &Super) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(format!("(super {})", super_expr.method.text)))
+    }
+
+    fn visit_array_expr(&mut self, array_expr: &ArrayExpr) -> Result<Option<Self::R>, Self::E> {
+        let elements: Vec<&Expr> = array_expr.elements.iter().collect();
+        Ok(Some(self.parenthesize("array", &elements)))
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &MapExpr) -> Result<Option<Self::R>, Self::E> {
+        let entries: Vec<String> = map_expr.entries.iter()
+            .map(|(key, value)| format!(" ({} {})", self.expr(key), self.expr(value)))
+            .collect();
+        Ok(Some(format!("(map{})", entries.concat())))
+    }
+
+    fn visit_subscript_get_expr(&mut self, subscript_get_expr: &SubscriptGetExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.parenthesize("[]", &[&subscript_get_expr.array, &subscript_get_expr.index])))
+    }
+
+    fn visit_subscript_set_expr(&mut self, subscript_set_expr: &SubscriptSetExpr) -> Result<Option<Self::R>, Self::E> {
+        let target = self.parenthesize("[]", &[&subscript_set_expr.array, &subscript_set_expr.index]);
+        let op = self.assignment_op(&subscript_set_expr.op, subscript_set_expr.postfix);
+        Ok(Some(format!("({op} {target} {})", self.expr(&subscript_set_expr.value))))
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &LambdaExpr) -> Result<Option<Self::R>, Self::E> {
+        Ok(Some(self.lambda(&lambda_expr.decl)))
+    }
+
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &InterpolationExpr) -> Result<Option<Self::R>, Self::E> {
+        let parts: Vec<&Expr> = interpolation_expr.parts.iter().collect();
+        Ok(Some(self.parenthesize("interpolate", &parts)))
+    }
+}
@@ -6,13 +6,19 @@ pub enum Stmt {
     Print(PrintStmt),
     If(IfStmt),
     While(WhileStmt),
+    ForIn(ForInStmt),
     Break,
     Continue,
     Return(ReturnStmt),
     Block(Block),
     VarDecl(VarDecl),
     FunDecl(FunDecl),
-    ClassDecl(ClassDecl)
+    ClassDecl(ClassDecl),
+    Import(ImportStmt),
+    Export(ExportStmt),
+    Throw(ThrowStmt),
+    Try(TryStmt),
+    Error(ErrorStmt)
 }
 
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -39,6 +45,15 @@ pub struct WhileStmt {
     pub for_update: Option<Box<Stmt>>
 }
 
+/// `for (var name in iterable) body`; `keyword` is the `in`, which locates errors about the iterable.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ForInStmt {
+    pub name: Token,
+    pub keyword: Token,
+    pub iterable: Expr,
+    pub body: Box<Stmt>
+}
+
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct ReturnStmt {
     pub keyword: Token,
@@ -68,4 +83,57 @@ pub struct ClassDecl {
     pub name: Token,
     pub superclass: Option<Identifier>,
     pub methods: Vec<FunDecl>
+}
+
+/// `import "path";` brings in every export of the module, `import { a, b } from "path";` only the listed ones.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ImportStmt {
+    pub keyword: Token,
+    pub path: Token,
+    pub names: Option<Vec<Token>>
+}
+
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ExportStmt {
+    pub keyword: Token,
+    pub decl: Box<Stmt>
+}
+
+impl ExportStmt {
+    /// Name bound by the exported declaration.
+    pub fn name(&self) -> Option<&Token> {
+        match &*self.decl {
+            Stmt::VarDecl(var_decl) => Some(&var_decl.name),
+            Stmt::FunDecl(fun_decl) => Some(&fun_decl.name),
+            Stmt::ClassDecl(class_decl) => Some(&class_decl.name),
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ThrowStmt {
+    pub keyword: Token,
+    pub value: Expr
+}
+
+/// `try { body } catch (name) { ... } finally { ... }`; at least one of the clauses is present.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct TryStmt {
+    pub keyword: Token,
+    pub body: Vec<Stmt>,
+    pub catch_clause: Option<CatchClause>,
+    pub finally_body: Option<Vec<Stmt>>
+}
+
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct CatchClause {
+    pub name: Token,
+    pub body: Vec<Stmt>
+}
+
+/// Placeholder for a declaration that failed to parse; `token` is where the error was reported.
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct ErrorStmt {
+    pub token: Token
 }
\ No newline at end of file
@@ -1,7 +1,12 @@
 use std::rc::Rc;
-use crate::interpreter::{Interpreter, Value};
+use crate::interpreter::{ErrType, Interpreter, Value};
 
 pub trait Callable {
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value>;
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType>;
     fn arity(&self) -> usize;
+
+    /// How many arguments may follow the required ones; the callee sees only those that were passed.
+    fn optional_arity(&self) -> usize {
+        0
+    }
 }
\ No newline at end of file
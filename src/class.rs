@@ -33,6 +33,14 @@ impl Class {
         self.weak_self = None;
     }
 
+    pub fn methods(&self) -> impl Iterator<Item = &Rc<RefCell<Function>>> {
+        self.methods.values()
+    }
+
+    pub fn superclass(&self) -> Option<Rc<RefCell<Class>>> {
+        self.superclass.clone()
+    }
+
     pub fn find_method(&self, name: String) -> Option<Rc<RefCell<Function>>> {
         if let Some(method) = self.methods.get(&name) {
             Some(method.clone())
@@ -45,14 +53,19 @@ impl Class {
 }
 
 impl Callable for Class {
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value> {
-        let instance = Rc::new(RefCell::new(Instance::new(self.weak_self.as_ref().unwrap().clone())));
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let instance = interpreter.heap.instance(Instance::new(self.weak_self.as_ref().unwrap().upgrade().unwrap()));
         instance.borrow_mut().set_weak_self(Rc::downgrade(&instance));
         let initializer = self.find_method("init".to_string());
+        interpreter.push_frame(self.name.clone());
         if let Some(initializer) = initializer {
-            initializer.borrow().bind(instance.clone()).call(interpreter, arguments);
+            let result = initializer.borrow().bind(instance.clone()).call(interpreter, arguments);
+            interpreter.leave_frame(&result);
+            result?;
+        } else {
+            interpreter.pop_frame();
         }
-        Rc::new(Value::Instance(instance))
+        Ok(Rc::new(Value::Instance(instance)))
     }
 
     fn arity(&self) -> usize {
@@ -0,0 +1,182 @@
+use std::fmt;
+use std::rc::Rc;
+use crate::token::Token;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorFormat {
+    Human,
+    Json
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    Scanner,
+    Parser,
+    Resolver,
+    Runtime
+}
+
+impl Stage {
+    /// Every diagnostic carries the code of the stage that produced it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Scanner => "E0100",
+            Self::Parser => "E0200",
+            Self::Resolver => "E0300",
+            Self::Runtime => "E0400"
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Scanner => "scanner",
+            Self::Parser => "parser",
+            Self::Resolver => "resolver",
+            Self::Runtime => "runtime"
+        }
+    }
+}
+
+/// Location of a diagnostic: byte offsets `start..end` plus the 1-based line and column of `start`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+impl From<&Token> for Span {
+    fn from(token: &Token) -> Self {
+        Self {
+            file: token.file.clone(),
+            start: token.start,
+            end: token.start + token.text.len(),
+            line: token.line,
+            column: token.column
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub stage: Stage,
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub notes: Vec<String>
+}
+
+impl Diagnostic {
+    pub fn error(stage: Stage, message: String, span: Span) -> Self {
+        Self {
+            stage,
+            severity: Severity::Error,
+            code: stage.code(),
+            message,
+            span,
+            notes: Vec::new()
+        }
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    /// Renders the diagnostic rustc-style, underlining the span in `source` when the line is available.
+    pub fn render(&self, source: &str) -> String {
+        let line_number = self.span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let mut result = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        result.push_str(&format!("{gutter}--> {}:{}:{}\n", self.span.file, self.span.line, self.span.column));
+        // without the source, e.g. for a call made from Rust, there is nothing to underline
+        if !source.is_empty() && let Some(line) = source.split('\n').nth(self.span.line - 1) {
+            let line_start = source.split('\n').take(self.span.line - 1).map(|line| line.len() + 1).sum::<usize>();
+            let line = line.trim_end_matches('\r');
+            let line_end = line_start + line.len();
+            let underline = source.get(self.span.start.min(line_end)..self.span.end.min(line_end))
+                .map_or(0, |text| text.chars().count())
+                .max(1);
+            result.push_str(&format!("{gutter} |\n"));
+            result.push_str(&format!("{line_number} | {line}\n"));
+            result.push_str(&format!("{gutter} | {}{}\n", " ".repeat(self.span.column - 1), "^".repeat(underline)));
+        }
+        for note in &self.notes {
+            result.push_str(&format!("{gutter} = note: {note}\n"));
+        }
+        result
+    }
+
+    /// Renders the diagnostic as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let notes: Vec<String> = self.notes.iter().map(|note| json_string(note)).collect();
+        format!(
+            "{{\"file\":{},\"start\":{},\"end\":{},\"line\":{},\"column\":{},\"stage\":\"{}\",\"severity\":\"{}\",\"code\":\"{}\",\"message\":{},\"notes\":[{}]}}",
+            json_string(&self.span.file), self.span.start, self.span.end, self.span.line, self.span.column,
+            self.stage.name(), self.severity, self.code, json_string(&self.message), notes.join(",")
+        )
+    }
+}
+
+/// The diagnostics of a failed evaluation, already rendered against the source they point into.
+#[derive(Clone, Debug)]
+pub struct Diagnostics {
+    pub diagnostics: Vec<Diagnostic>,
+    rendered: String
+}
+
+impl Diagnostics {
+    /// `source` returns the text of the file a diagnostic points into.
+    pub fn new<'a>(diagnostics: Vec<Diagnostic>, source: impl Fn(&str) -> &'a str) -> Self {
+        let rendered = diagnostics.iter()
+            .map(|diagnostic| diagnostic.render(source(&diagnostic.span.file)))
+            .collect();
+        Self {
+            diagnostics,
+            rendered
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rendered)
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c)
+        }
+    }
+    result.push('"');
+    result
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning")
+        }
+    }
+}
@@ -19,11 +19,36 @@ impl Environment {
         }
     }
 
+    /// The outermost environment of the chain: the globals of the module `environment` belongs to.
+    pub fn root(environment: &Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        let mut root = environment.clone();
+        loop {
+            let enclosing = root.borrow().enclosing.clone();
+            match enclosing {
+                Some(enclosing) => root = enclosing,
+                None => return root
+            }
+        }
+    }
+
     pub fn define(&mut self, name: String, value: Rc<Value>) {
         self.values.insert(name, value);
     }
 
-    pub fn get(&self, name: &Token) -> Result<Rc<Value>, (Token, String)> {
+    /// Value defined directly in this environment, ignoring the enclosing ones.
+    pub fn get_own(&self, name: &str) -> Option<Rc<Value>> {
+        self.values.get(name).cloned()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Rc<Value>> {
+        self.values.values()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Rc<Value>, (Box<Token>, String)> {
         if let Some(value) = self.values.get(&name.text) {
             return Ok(value.clone());
         }
@@ -31,10 +56,10 @@ impl Environment {
         if let Some(enclosing) = &self.enclosing {
             return enclosing.borrow().get(name);
         }
-        Err((name.clone(), format!("Undefined variable '{}'.", name.text)))
+        Err((Box::new(name.clone()), format!("Undefined variable '{}'.", name.text)))
     }
 
-    pub fn assign(&mut self, name: &Token, value: Rc<Value>) -> Result<(), (Token, String)> {
+    pub fn assign(&mut self, name: &Token, value: Rc<Value>) -> Result<(), (Box<Token>, String)> {
         if self.values.contains_key(&name.text) {
             self.values.insert(name.text.clone(), value);
             return Ok(());
@@ -43,7 +68,7 @@ impl Environment {
         if let Some(enclosing) = &self.enclosing {
             return enclosing.borrow_mut().assign(name, value);
         }
-        Err((name.clone(), format!("Undefined variable '{}'.", name.text)))
+        Err((Box::new(name.clone()), format!("Undefined variable '{}'.", name.text)))
     }
 
     pub fn assign_at(&mut self, distance: usize, name: &Token, value: Rc<Value>) {
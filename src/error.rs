@@ -1,3 +1,5 @@
+use crate::diagnostic::Span;
+
 pub trait ErrorReporter {
-    fn error(&self, start: usize, end: usize, error_content: String);
+    fn error(&self, span: Span, error_content: String);
 }
\ No newline at end of file
@@ -17,6 +17,12 @@ impl Function {
         }
     }
 
+    pub fn closure(&self) -> Rc<RefCell<Environment>> {
+        self.closure.clone()
+    }
+
+    /// The environment holding `this` is not tracked by the heap: the only thing it
+    /// references is the instance, so any cycle through it also goes through the instance.
     pub fn bind(&self, instance: Rc<RefCell<Instance>>) -> Self {
         let environment = Rc::new(RefCell::new(Environment::new(Some(self.closure.clone()))));
         environment.borrow_mut().define("this".to_string(), Rc::new(Value::Instance(instance)));
@@ -29,24 +35,25 @@ impl Function {
 }
 
 impl Callable for Function {
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value> {
-        let environment = Rc::new(RefCell::new(Environment::new(Some(self.closure.clone()))));
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let environment = interpreter.heap.environment(Some(self.closure.clone()));
         for i in 0..self.decl.params.len() {
             environment.borrow_mut().define(
                 self.decl.params[i].text.clone(), 
                 arguments[i].clone());
         }
+        interpreter.push_frame(self.decl.name.text.clone());
         let result = interpreter.execute_block(&self.decl.body, environment);
-        if let Err(ErrType::Return(value)) = result {            
-            if self.is_initializer {
-                return self.closure.borrow().get_at(0, "this".to_string());
-            }
-            return value;
-        }
+        interpreter.leave_frame(&result);
+        let value = match result {
+            Ok(()) => Rc::new(Value::Nil),
+            Err(ErrType::Return(value)) => value,
+            Err(error) => return Err(error)
+        };
         if self.is_initializer {
-            return self.closure.borrow().get_at(0, "this".to_string());
+            return Ok(self.closure.borrow().get_at(0, "this".to_string()));
         }
-        Rc::new(Value::Nil)
+        Ok(value)
     }
 
     fn arity(&self) -> usize {
@@ -0,0 +1,263 @@
+use std::{cell::RefCell, collections::HashMap, rc::{Rc, Weak}};
+use crate::{array::Array, class::Class, environment::Environment, function::Function, instance::Instance, interpreter::Value, map::Map};
+
+// live objects needed before the first automatic collection
+const INITIAL_THRESHOLD: usize = 1024;
+
+/// Keeps track of the environments, instances, arrays and maps the interpreter allocates,
+/// and frees the ones that are only kept alive by reference cycles among themselves.
+///
+/// Objects are still reference counted; `collect` is a cycle collector on top of that. It counts
+/// how many references to each object come from other objects. An object with more references
+/// than that is held from outside the object graph (a global, the current environment, a value
+/// on the Rust stack) and is a root. Whatever cannot be reached from a root is garbage, and
+/// clearing its contents breaks the cycles so that the counts drop to zero.
+pub struct Heap {
+    objects: Vec<Tracked>,
+    // live objects at which `track` collects
+    next_gc: usize,
+    // length of `objects` at which `track` drops the entries of objects that have been freed
+    next_prune: usize
+}
+
+enum Tracked {
+    Environment(Weak<RefCell<Environment>>),
+    Instance(Weak<RefCell<Instance>>),
+    Array(Weak<RefCell<Array>>),
+    Map(Weak<RefCell<Map>>)
+}
+
+impl Tracked {
+    fn is_alive(&self) -> bool {
+        match self {
+            Self::Environment(weak) => weak.strong_count() > 0,
+            Self::Instance(weak) => weak.strong_count() > 0,
+            Self::Array(weak) => weak.strong_count() > 0,
+            Self::Map(weak) => weak.strong_count() > 0
+        }
+    }
+
+    fn upgrade(&self) -> Option<Node> {
+        match self {
+            Self::Environment(weak) => weak.upgrade().map(Node::Environment),
+            Self::Instance(weak) => weak.upgrade().map(Node::Instance),
+            Self::Array(weak) => weak.upgrade().map(Node::Array),
+            Self::Map(weak) => weak.upgrade().map(Node::Map)
+        }
+    }
+}
+
+/// A shared allocation that can be part of a cycle.
+enum Node {
+    Value(Rc<Value>),
+    Environment(Rc<RefCell<Environment>>),
+    Function(Rc<RefCell<Function>>),
+    Class(Rc<RefCell<Class>>),
+    Instance(Rc<RefCell<Instance>>),
+    Array(Rc<RefCell<Array>>),
+    Map(Rc<RefCell<Map>>)
+}
+
+impl Node {
+    /// Only values that point at other objects are worth tracing.
+    fn from_value(value: &Rc<Value>) -> Option<Self> {
+        match &**value {
+            Value::Function(_) | Value::Class(_) | Value::Instance(_) | Value::Array(_) | Value::Map(_) => Some(Self::Value(value.clone())),
+            _ => None
+        }
+    }
+
+    fn address(&self) -> usize {
+        match self {
+            Self::Value(value) => Rc::as_ptr(value) as *const () as usize,
+            Self::Environment(environment) => Rc::as_ptr(environment) as *const () as usize,
+            Self::Function(function) => Rc::as_ptr(function) as *const () as usize,
+            Self::Class(class) => Rc::as_ptr(class) as *const () as usize,
+            Self::Instance(instance) => Rc::as_ptr(instance) as *const () as usize,
+            Self::Array(array) => Rc::as_ptr(array) as *const () as usize,
+            Self::Map(map) => Rc::as_ptr(map) as *const () as usize
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Self::Value(value) => Rc::strong_count(value),
+            Self::Environment(environment) => Rc::strong_count(environment),
+            Self::Function(function) => Rc::strong_count(function),
+            Self::Class(class) => Rc::strong_count(class),
+            Self::Instance(instance) => Rc::strong_count(instance),
+            Self::Array(array) => Rc::strong_count(array),
+            Self::Map(map) => Rc::strong_count(map)
+        }
+    }
+
+    /// The objects this one references, or `None` when it is mutably borrowed and cannot be read.
+    fn children(&self) -> Option<Vec<Node>> {
+        let mut children = Vec::new();
+        match self {
+            Self::Value(value) => children.push(match &**value {
+                Value::Function(function) => Self::Function(function.clone()),
+                Value::Class(class) => Self::Class(class.clone()),
+                Value::Instance(instance) => Self::Instance(instance.clone()),
+                Value::Array(array) => Self::Array(array.clone()),
+                Value::Map(map) => Self::Map(map.clone()),
+                _ => return Some(children)
+            }),
+            Self::Environment(environment) => {
+                let environment = environment.try_borrow().ok()?;
+                children.extend(environment.enclosing.clone().map(Self::Environment));
+                children.extend(environment.values().filter_map(Self::from_value));
+            }
+            Self::Function(function) => children.push(Self::Environment(function.try_borrow().ok()?.closure())),
+            Self::Class(class) => {
+                let class = class.try_borrow().ok()?;
+                children.extend(class.methods().map(|method| Self::Function(method.clone())));
+                children.extend(class.superclass().map(Self::Class));
+            }
+            Self::Instance(instance) => {
+                let instance = instance.try_borrow().ok()?;
+                children.push(Self::Class(instance.class()));
+                children.extend(instance.fields().filter_map(Self::from_value));
+            }
+            Self::Array(array) => children.extend(array.try_borrow().ok()?.elements.iter().filter_map(Self::from_value)),
+            Self::Map(map) => children.extend(map.try_borrow().ok()?.values().iter().filter_map(Self::from_value))
+        }
+        Some(children)
+    }
+
+    /// Drops every reference the object holds. Only called on garbage, which nothing can observe.
+    fn clear(&self) {
+        match self {
+            Self::Environment(environment) => if let Ok(mut environment) = environment.try_borrow_mut() {
+                environment.clear();
+            },
+            Self::Instance(instance) => if let Ok(mut instance) = instance.try_borrow_mut() {
+                instance.clear();
+            },
+            Self::Array(array) => if let Ok(mut array) = array.try_borrow_mut() {
+                array.elements.clear();
+            },
+            Self::Map(map) => if let Ok(mut map) = map.try_borrow_mut() {
+                map.clear();
+            },
+            // every cycle goes through one of the mutable objects above
+            Self::Value(_) | Self::Function(_) | Self::Class(_) => {}
+        }
+    }
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            next_gc: INITIAL_THRESHOLD,
+            next_prune: INITIAL_THRESHOLD
+        }
+    }
+
+    pub fn environment(&mut self, enclosing: Option<Rc<RefCell<Environment>>>) -> Rc<RefCell<Environment>> {
+        let environment = Rc::new(RefCell::new(Environment::new(enclosing)));
+        self.track(Tracked::Environment(Rc::downgrade(&environment)));
+        environment
+    }
+
+    pub fn instance(&mut self, instance: Instance) -> Rc<RefCell<Instance>> {
+        let instance = Rc::new(RefCell::new(instance));
+        self.track(Tracked::Instance(Rc::downgrade(&instance)));
+        instance
+    }
+
+    pub fn array(&mut self, elements: Vec<Rc<Value>>) -> Rc<RefCell<Array>> {
+        let array = Rc::new(RefCell::new(Array::new(elements)));
+        self.track(Tracked::Array(Rc::downgrade(&array)));
+        array
+    }
+
+    pub fn map(&mut self, map: Map) -> Rc<RefCell<Map>> {
+        let map = Rc::new(RefCell::new(map));
+        self.track(Tracked::Map(Rc::downgrade(&map)));
+        map
+    }
+
+    /// Number of tracked objects that are still alive.
+    pub fn size(&mut self) -> usize {
+        self.objects.retain(Tracked::is_alive);
+        self.objects.len()
+    }
+
+    /// Frees the objects that are only reachable from cycles and returns how many there were.
+    pub fn collect(&mut self) -> usize {
+        let before = self.size();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut indices: HashMap<usize, usize> = HashMap::new();
+        for object in &self.objects {
+            if let Some(node) = object.upgrade() {
+                indices.insert(node.address(), nodes.len());
+                nodes.push(node);
+            }
+        }
+
+        // discover everything reachable from the tracked objects and count the references between them
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        let mut internal = vec![0; nodes.len()];
+        let mut traced = vec![false; nodes.len()];
+        let mut pending: Vec<usize> = (0..nodes.len()).collect();
+        while let Some(index) = pending.pop() {
+            let Some(children) = nodes[index].children() else {
+                continue;
+            };
+            traced[index] = true;
+            for child in children {
+                let child = *indices.entry(child.address()).or_insert_with(|| {
+                    nodes.push(child);
+                    edges.push(Vec::new());
+                    internal.push(0);
+                    traced.push(false);
+                    pending.push(nodes.len() - 1);
+                    nodes.len() - 1
+                });
+                edges[index].push(child);
+                internal[child] += 1;
+            }
+        }
+
+        // `nodes` holds one reference to each object itself; any other one not counted above is a root
+        let mut reachable = vec![false; nodes.len()];
+        let mut pending: Vec<usize> = (0..nodes.len())
+            .filter(|&index| !traced[index] || nodes[index].strong_count() - 1 > internal[index])
+            .collect();
+        while let Some(index) = pending.pop() {
+            if !reachable[index] {
+                reachable[index] = true;
+                pending.extend(&edges[index]);
+            }
+        }
+        for (node, reachable) in nodes.iter().zip(reachable) {
+            if !reachable {
+                node.clear();
+            }
+        }
+        drop(nodes);
+        before - self.size()
+    }
+
+    fn track(&mut self, object: Tracked) {
+        self.objects.push(object);
+        if self.objects.len() < self.next_prune {
+            return;
+        }
+        // most objects die young and are freed by their counts, so only collect
+        // once the live ones have doubled since the last collection
+        if self.size() >= self.next_gc {
+            self.collect();
+            self.next_gc = (self.objects.len() * 2).max(INITIAL_THRESHOLD);
+        }
+        self.next_prune = (self.objects.len() * 2).max(INITIAL_THRESHOLD);
+    }
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,13 +4,13 @@ use crate::{class::Class, interpreter::{ErrType, Value}, token::Token};
 
 
 pub struct Instance {
-    class: Weak<RefCell<Class>>,
+    class: Rc<RefCell<Class>>,
     fields: HashMap<String, Rc<Value>>,
     weak_self: Option<Weak<RefCell<Self>>>
 }
 
 impl Instance {
-    pub fn new(class: Weak<RefCell<Class>>) -> Self {
+    pub fn new(class: Rc<RefCell<Class>>) -> Self {
         Self {
             class: class,
             fields: HashMap::new(),
@@ -26,15 +26,35 @@ impl Instance {
         self.weak_self = None;
     }
 
+    pub fn class(&self) -> Rc<RefCell<Class>> {
+        self.class.clone()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &Rc<Value>> {
+        self.fields.values()
+    }
+
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn field(&self, name: &str) -> Option<Rc<Value>> {
+        self.fields.get(name).cloned()
+    }
+
+    pub fn set_field(&mut self, name: &str, value: Rc<Value>) {
+        self.fields.insert(name.to_string(), value);
+    }
+
     pub fn get(&self, name: &Token) -> Result<Rc<Value>, ErrType> {
         if let Some(field) = self.fields.get(&name.text) {
             return Ok(field.to_owned());
         }
-        if let Some(method) = self.class.upgrade().unwrap().borrow().find_method(name.text.clone()) {
+        if let Some(method) = self.class.borrow().find_method(name.text.clone()) {
             let bind = method.borrow().bind(self.weak_self.as_ref().unwrap().upgrade().unwrap());
             return Ok(Rc::new(Value::Function(Rc::new(RefCell::new(bind)))));
         }
-        Err(ErrType::Err(name.clone(), format!("Undefined property '{}'.", name.text)))
+        Err(ErrType::Err(Box::new(name.clone()), format!("Undefined property '{}'.", name.text)))
     }
 
     pub fn set(&mut self, name: &Token, value: Rc<Value>) {
@@ -44,12 +64,12 @@ impl Instance {
 
 impl ToString for Instance {
     fn to_string(&self) -> String {
-        format!("{} instance", self.class.upgrade().unwrap().borrow().name)
+        format!("{} instance", self.class.borrow().name)
     }
 }
 
 impl PartialEq for Instance {
     fn eq(&self, other: &Self) -> bool {
-        self.class.upgrade().unwrap() == other.class.upgrade().unwrap() && self.fields == other.fields
+        self.class == other.class && self.fields == other.fields
     }
 }
\ No newline at end of file
@@ -1,9 +1,15 @@
 use crate::array::Array;
+use crate::map::{Map, MapKey};
 use crate::class::Class;
+use crate::diagnostic::{Diagnostic, Span, Stage};
 use crate::environment::Environment;
 use crate::function::Function;
+use crate::heap::Heap;
+use crate::random::Random;
 use crate::callable::Callable;
 use crate::instance::Instance;
+use crate::module::{self, Module};
+use crate::output::Output;
 use crate::native::{init_native_functions, NativeFunction};
 use crate::token::{Literal, Token, TokenType};
 use crate::visit::*;
@@ -11,14 +17,35 @@ use crate::ast::{expr::*, stmt::*};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 pub struct Interpreter {
     pub globals: Rc<RefCell<Environment>>,
+    // `print` and natives write to `output`; diagnostics are written by whoever runs the interpreter
+    pub output: Output,
     environment: Rc<RefCell<Environment>>,
     locals: HashMap<Expr, usize>,
+    modules: HashMap<PathBuf, Module>,
+    frames: Vec<CallFrame>,
+    // the `(` of the call being made, recorded by the frame the callee pushes
+    call_site: Option<Token>,
+    pub heap: Heap,
+    pub random: Random,
+    // class of the instances `catch` binds for runtime errors
+    error_class: Rc<RefCell<Class>>
 }
 
+/// A call in progress: the callee's name and the `(` of the call that entered it.
+pub struct CallFrame {
+    pub name: String,
+    pub call_site: Option<Token>
+}
+
+// deep recursion would otherwise print one line per call
+const MAX_TRACEBACK: usize = 16;
+
 impl Visitor for Interpreter {
     type R = Rc<Value>;
     type E = ErrType;
@@ -31,7 +58,7 @@ impl Visitor for Interpreter {
         let value = if let Some(value) = self.visit_expr(&*unary_expr.expr)? {
             value
         } else {
-            return Err(ErrType::Err(unary_expr.op.clone(), "".to_string()));
+            return Err(ErrType::Err(Box::new(unary_expr.op.clone()), "".to_string()));
         };
 
         match unary_expr.op.token_type {
@@ -42,6 +69,10 @@ impl Visitor for Interpreter {
             TokenType::Bang => {
                 return Ok(Some(Rc::new(Value::Bool(!self.is_truthy(value)))));
             },
+            TokenType::Tilde => {
+                let value = self.integer_operand(&unary_expr.op, &value)?;
+                return Ok(Some(Rc::new(Value::Number(!value as f64))));
+            },
             _ => {}
         };
         Ok(None)
@@ -51,99 +82,26 @@ impl Visitor for Interpreter {
         let _lhs = self.visit_expr(&*binary_expr.lhs)?;
         let _rhs = self.visit_expr(&*binary_expr.rhs)?;
         if _lhs.is_none() || _rhs.is_none() {
-            return Err(ErrType::Err(binary_expr.op.clone(), "".to_string()));
+            return Err(ErrType::Err(Box::new(binary_expr.op.clone()), "".to_string()));
         }
         let (lhs, rhs) = (_lhs.as_ref().unwrap(), _rhs.as_ref().unwrap());
-        
-        match binary_expr.op.token_type {
-            TokenType::Minus => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() - rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Number(result))));
-            },
-            TokenType::Slash => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                if rhs.as_number().unwrap() == 0.0 {
-                    return Err(ErrType::Err(binary_expr.op.clone(), "Cannot divide by 0.".to_string()));
-                }
-                let result = lhs.as_number().unwrap() / rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Number(result))));
-            },
-            TokenType::Star => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() * rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Number(result))));
-            },
-            TokenType::Plus => {
-                if let (Some(lhs), Some(rhs)) = (lhs.as_number(), rhs.as_number()) {
-                    let result = lhs + rhs;
-                    return Ok(Some(Rc::new(Value::Number(result))));
-                } else if let (Some(lhs), Some(rhs)) = (lhs.as_string(), rhs.as_string()) {
-                    let result = lhs + &rhs;
-                    return Ok(Some(Rc::new(Value::String(result))));
-                } else if let (Some(lhs), Some(rhs)) = (lhs.as_number(), rhs.as_string()) {
-                    let result = lhs.to_string() + &rhs;
-                    return Ok(Some(Rc::new(Value::String(result))));
-                } else if let (Some(lhs), Some(rhs)) = (lhs.as_string(), rhs.as_number()) {
-                    let result = lhs + &rhs.to_string();
-                    return Ok(Some(Rc::new(Value::String(result))));
-                } else {
-                    return Err(ErrType::Err(binary_expr.op.clone(), "Operands must be two numbers or two strings.".to_string()));
-                }
-            },
-
-            TokenType::Greater => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() > rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Bool(result))));
-            },
-            TokenType::GreaterEqual => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() >= rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Bool(result))));
-            },
-            TokenType::Less => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() < rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Bool(result))));
-            },
-            TokenType::LessEqual => {
-                self.check_number_operands(&binary_expr.op, lhs, rhs)?;
-                let result = lhs.as_number().unwrap() <= rhs.as_number().unwrap();
-                return Ok(Some(Rc::new(Value::Bool(result))));
-            },
-
-            TokenType::BangEqual => {
-                return Ok(Some(Rc::new(Value::Bool(!self.is_equal(lhs, rhs)))));
-            },
-            TokenType::EqualEqual => {
-                return Ok(Some(Rc::new(Value::Bool(self.is_equal(lhs, rhs)))));
-            },
-            TokenType::Comma => {
-                return Ok(Some(rhs.clone()));
-            }
-
-            _ => {}
-        }
-
-        Ok(None)
+        self.binary(&binary_expr.op, &binary_expr.op.token_type, lhs, rhs)
     }
     
     fn visit_assign_expr(&mut self, assign_expr: &AssignExpr) -> Result<Option<Self::R>, Self::E> {
-        let value = {
-            if let Some(value) = self.visit_expr(&assign_expr.value)? {
-                value
-            } else {
-                Rc::new(Value::Nil)
-            }
+        let expr = Expr::Assign(assign_expr.to_owned());
+        let current = match assign_expr.op {
+            Some(_) => self.look_up_variable(&assign_expr.name, expr.clone())?,
+            None => None
         };
-        if let Some(distance) = self.locals.get(&Expr::Assign(assign_expr.to_owned())) {
-            self.environment.borrow_mut().assign_at(*distance, &assign_expr.name, value.clone());
+        let (value, result) = self.assigned_value(&assign_expr.op, assign_expr.postfix, current, &assign_expr.value)?;
+        if let Some(distance) = self.locals.get(&expr) {
+            self.environment.borrow_mut().assign_at(*distance, &assign_expr.name, value);
         }
-        else if let Err((token, message)) = self.environment.borrow_mut().assign(&assign_expr.name, value.clone()) {
+        else if let Err((token, message)) = Environment::root(&self.environment).borrow_mut().assign(&assign_expr.name, value) {
             return Err(ErrType::Err(token, message));
         };
-        Ok(Some(value))
+        Ok(Some(result))
     }
 
     fn visit_ternary_expr(&mut self, ternary_expr: &TernaryExpr) -> Result<Option<Self::R>, Self::E> {
@@ -172,20 +130,12 @@ impl Visitor for Interpreter {
     }
 
     fn visit_call_expr(&mut self, call_expr: &CallExpr) -> Result<Option<Self::R>, Self::E> {
-        let callee = self.visit_expr(&call_expr.name)?;
-        let callable = if callee.is_some() && let Some(callable) = callee.unwrap().as_callable() {
-            callable
-        } else {
-            return Err(ErrType::Err(call_expr.paren.clone(), "Can only call functions and classes.".to_string()));
-        };
-        if call_expr.args.len() != callable.borrow().arity() {
-            return Err(ErrType::Err(call_expr.paren.clone(), format!("Expected {} arguments but got {}.", call_expr.args.len(), callable.borrow().arity())));
-        }
+        let callee = self.visit_expr(&call_expr.name)?.unwrap_or(Rc::new(Value::Nil));
         let mut arguments = Vec::new();
         for argument in &call_expr.args {
             arguments.push(self.visit_expr(argument)?.unwrap());
         }
-        Ok(Some(callable.borrow().call(self, arguments)))
+        Ok(Some(self.call(&callee, arguments, &call_expr.paren)?))
     }
 
     fn visit_get_expr(&mut self, get_expr: &GetExpr) -> Result<Option<Self::R>, Self::E> {
@@ -197,19 +147,21 @@ impl Visitor for Interpreter {
                 return Ok(Some(field));
             }
         }
-        Err(ErrType::Err(get_expr.name.clone(), "Only instances have properties.".to_string()))
+        Err(ErrType::Err(Box::new(get_expr.name.clone()), "Only instances have properties.".to_string()))
     }
 
     fn visit_set_expr(&mut self, set_expr: &SetExpr) -> Result<Option<Self::R>, Self::E> {
         let object = self.visit_expr(&set_expr.object)?;
         if object.is_some() && let Some(instance) = object.unwrap().as_instance() {
-            let value = self.visit_expr(&set_expr.value)?;
-            if let Some(value) = value.as_ref() {
-                instance.borrow_mut().set(&set_expr.name, value.to_owned());
+            let current = match set_expr.op {
+                Some(_) => Some(instance.borrow().get(&set_expr.name)?),
+                None => None
             };
-            return Ok(value);
+            let (value, result) = self.assigned_value(&set_expr.op, set_expr.postfix, current, &set_expr.value)?;
+            instance.borrow_mut().set(&set_expr.name, value);
+            return Ok(Some(result));
         }
-        Err(ErrType::Err(set_expr.name.clone(), "Only instances have fields.".to_string()))
+        Err(ErrType::Err(Box::new(set_expr.name.clone()), "Only instances have fields.".to_string()))
     }
 
     fn visit_identifier(&mut self, identifier: &Identifier) -> Result<Option<Self::R>, Self::E> {
@@ -229,7 +181,7 @@ impl Visitor for Interpreter {
             if let Some(method) = method {
                 return Ok(Some(Rc::new(Value::Function(Rc::new(RefCell::new(method.borrow().bind(instance.clone())))))));
             } else {
-                return Err(ErrType::Err(super_expr.method.clone(), format!("Undefined property'{}'.", super_expr.method.text)));
+                return Err(ErrType::Err(Box::new(super_expr.method.clone()), format!("Undefined property '{}'.", super_expr.method.text)));
             }
         }
 
@@ -242,41 +194,98 @@ impl Visitor for Interpreter {
         for element in &array_expr.elements {
             elements.push(self.visit_expr(element)?.unwrap());
         }
-        Ok(Some(Rc::new(Value::Array(Rc::new(RefCell::new(Array::new(elements)))))))
+        Ok(Some(Rc::new(Value::Array(self.heap.array(elements)))))
+    }
+
+    fn visit_map_expr(&mut self, map_expr: &MapExpr) -> Result<Option<Self::R>, Self::E> {
+        let mut map = Map::new();
+        for (key, value) in &map_expr.entries {
+            let key = self.visit_expr(key)?.unwrap();
+            let key = self.map_key(&key, &map_expr.brace)?;
+            map.set(key, self.visit_expr(value)?.unwrap());
+        }
+        Ok(Some(Rc::new(Value::Map(self.heap.map(map)))))
+    }
+
+    fn visit_lambda_expr(&mut self, lambda_expr: &LambdaExpr) -> Result<Option<Self::R>, Self::E> {
+        let function = Function::new(lambda_expr.decl.clone(), self.environment.clone(), false);
+        Ok(Some(Rc::new(Value::Function(Rc::new(RefCell::new(function))))))
+    }
+
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &InterpolationExpr) -> Result<Option<Self::R>, Self::E> {
+        let mut result = String::new();
+        for part in &interpolation_expr.parts {
+            let value = self.visit_expr(part)?.unwrap_or(Rc::new(Value::Nil));
+            result.push_str(&value.to_string());
+        }
+        Ok(Some(Rc::new(Value::String(result))))
     }
 
     fn visit_subscript_get_expr(&mut self, subscript_get_expr: &SubscriptGetExpr) -> Result<Option<Self::R>, Self::E> {
-        let value = self.visit_expr(&*subscript_get_expr.array)?;
-        if let Some(value) = value && let Some(array) = value.as_array() {
-            let value = self.visit_expr(&*subscript_get_expr.index)?;
-            if let Some(value) = value &&
-               let Some(index) = value.as_number() && index.fract() == 0.0 {
-                let element = array.borrow().get(index as isize);
+        let bracket = &subscript_get_expr.bracket;
+        let target = self.visit_expr(&subscript_get_expr.array)?.unwrap();
+        let index = self.visit_expr(&subscript_get_expr.index)?.unwrap();
+        match &*target {
+            Value::Array(array) => {
+                let index = self.array_index(&index, bracket)?;
+                let element = array.borrow().get(index);
                 if let Some(element) = element {
-                    return Ok(Some(element))
+                    return Ok(Some(element));
+                }
+                Err(ErrType::Err(Box::new(bracket.clone()), "Index out of range.".to_string()))
+            }
+            Value::Map(map) => {
+                let key = self.map_key(&index, bracket)?;
+                let value = map.borrow().get(&key);
+                if let Some(value) = value {
+                    return Ok(Some(value));
                 }
-                return Err(ErrType::Err(subscript_get_expr.bracket.clone(), "Index out of range.".to_string()));
+                Err(ErrType::Err(Box::new(bracket.clone()), format!("Undefined key '{index}'.")))
             }
-            return Err(ErrType::Err(subscript_get_expr.bracket.clone(), "Index must be an integer.".to_string()));
+            Value::String(string) => {
+                let index = self.array_index(&index, bracket)?;
+                let length = string.chars().count() as isize;
+                let index = if index < 0 { length + index } else { index };
+                if index >= 0 && let Some(c) = string.chars().nth(index as usize) {
+                    return Ok(Some(Rc::new(Value::String(c.to_string()))));
+                }
+                Err(ErrType::Err(Box::new(bracket.clone()), "Index out of range.".to_string()))
+            }
+            _ => Err(ErrType::Err(Box::new(bracket.clone()), "Only arrays, maps and strings can be indexed.".to_string()))
         }
-        Err(ErrType::Err(subscript_get_expr.bracket.clone(), "Only arrays can be indexed.".to_string()))
     }
 
     fn visit_subscript_set_expr(&mut self, subscript_set_expr: &SubscriptSetExpr) -> Result<Option<Self::R>, Self::E> {
-        let value = self.visit_expr(&*subscript_set_expr.array)?;
-        if let Some(value) = value && let Some(array) = value.as_array() {
-            let value = self.visit_expr(&*subscript_set_expr.index)?;
-            if let Some(value) = value &&
-               let Some(index) = value.as_number() && index.fract() == 0.0 {
-                let value = self.visit_expr(&*subscript_set_expr.value)?.unwrap();
-                if array.borrow_mut().set(index as isize, value.clone()) {
-                    return Ok(Some(value));
+        let bracket = &subscript_set_expr.bracket;
+        let target = self.visit_expr(&subscript_set_expr.array)?.unwrap();
+        let index = self.visit_expr(&subscript_set_expr.index)?.unwrap();
+        let (op, postfix) = (&subscript_set_expr.op, subscript_set_expr.postfix);
+        match &*target {
+            Value::Array(array) => {
+                let index = self.array_index(&index, bracket)?;
+                let current = match op {
+                    Some(_) => Some(array.borrow().get(index).ok_or_else(|| ErrType::Err(Box::new(bracket.clone()), "Index out of range.".to_string()))?),
+                    None => None
+                };
+                let (value, result) = self.assigned_value(op, postfix, current, &subscript_set_expr.value)?;
+                if array.borrow_mut().set(index, value) {
+                    return Ok(Some(result));
                 }
-                return Err(ErrType::Err(subscript_set_expr.bracket.clone(), "Index out of range.".to_string()));
+                Err(ErrType::Err(Box::new(bracket.clone()), "Index out of range.".to_string()))
+            }
+            Value::Map(map) => {
+                let key = self.map_key(&index, bracket)?;
+                let current = match op {
+                    Some(_) => Some(map.borrow().get(&key).ok_or_else(|| ErrType::Err(Box::new(bracket.clone()), format!("Undefined key '{index}'.")))?),
+                    None => None
+                };
+                let (value, result) = self.assigned_value(op, postfix, current, &subscript_set_expr.value)?;
+                map.borrow_mut().set(key, value);
+                Ok(Some(result))
             }
-            return Err(ErrType::Err(subscript_set_expr.bracket.clone(), "Index must be an integer.".to_string()));
+            Value::String(_) => Err(ErrType::Err(Box::new(bracket.clone()), "Strings are immutable.".to_string())),
+            _ => Err(ErrType::Err(Box::new(bracket.clone()), "Only arrays, maps and strings can be indexed.".to_string()))
         }
-        Err(ErrType::Err(subscript_set_expr.bracket.clone(), "Only arrays can be indexed.".to_string()))
     }
 
     fn visit_var_decl(&mut self, var_decl: &VarDecl) -> Result<Option<Self::R>, Self::E> {
@@ -307,7 +316,7 @@ impl Visitor for Interpreter {
             if let Some(superclass) = superclass && let Value::Class(superclass) = &*superclass {
                 Some(superclass.clone())
             } else {
-                return Err(ErrType::Err(identifier.name.clone(), "Superclass must be a class.".to_string()));
+                return Err(ErrType::Err(Box::new(identifier.name.clone()), "Superclass must be a class.".to_string()));
             }
         } else {
             None
@@ -316,7 +325,7 @@ impl Visitor for Interpreter {
         self.environment.borrow_mut().define(class_decl.name.text.clone(), Rc::new(Value::Nil));
 
         if let Some(superclass) = &superclass {
-            self.environment = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+            self.environment = self.heap.environment(Some(self.environment.clone()));
             self.environment.borrow_mut().define("super".to_string(), Rc::new(Value::Class(superclass.clone())));
         }
 
@@ -344,8 +353,42 @@ impl Visitor for Interpreter {
         Ok(None)
     }
 
+    fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) -> Result<Option<Self::R>, Self::E> {
+        let value = self.visit_expr(&throw_stmt.value)?.unwrap();
+        Err(ErrType::Throw(Box::new(throw_stmt.keyword.clone()), value))
+    }
+
+    fn visit_try_stmt(&mut self, try_stmt: &TryStmt) -> Result<Option<Self::R>, Self::E> {
+        let depth = self.frames.len();
+        let environment = self.heap.environment(Some(self.environment.clone()));
+        let mut result = self.execute_block(&try_stmt.body, environment);
+        if let Some(catch_clause) = &try_stmt.catch_clause {
+            let error = match &result {
+                Err(ErrType::Err(token, message)) => Some(self.error_instance(token, message)),
+                Err(ErrType::Throw(_, value)) => Some(value.clone()),
+                _ => None
+            };
+            if let Some(error) = error {
+                // the calls the error unwound through are over
+                self.frames.truncate(depth);
+                let environment = self.heap.environment(Some(self.environment.clone()));
+                environment.borrow_mut().define(catch_clause.name.text.clone(), error);
+                result = self.execute_block(&catch_clause.body, environment);
+            }
+        }
+        if let Some(finally_body) = &try_stmt.finally_body {
+            // an error passing through keeps its frames for the traceback, but they are not part of `finally`
+            let unwound = self.frames.split_off(depth.min(self.frames.len()));
+            let environment = self.heap.environment(Some(self.environment.clone()));
+            self.execute_block(finally_body, environment)?;
+            self.frames.extend(unwound);
+        }
+        result?;
+        Ok(None)
+    }
+
     fn visit_block(&mut self, block: &Block) -> Result<Option<Self::R>, Self::E> {
-        let new_environment = Rc::new(RefCell::new(Environment::new(Some(self.environment.clone()))));
+        let new_environment = self.heap.environment(Some(self.environment.clone()));
         self.execute_block(&block.stmts, new_environment)?;
         Ok(None)
     }
@@ -384,6 +427,12 @@ impl Visitor for Interpreter {
         Ok(None)
     }
 
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Self::R>, Self::E> {
+        let iterable = self.visit_expr(&for_in_stmt.iterable)?.unwrap();
+        self.iterate(for_in_stmt, iterable)?;
+        Ok(None)
+    }
+
     fn visit_break_stmt(&mut self) -> Result<Option<Self::R>, Self::E> {
         Err(ErrType::Break)
     }
@@ -401,35 +450,189 @@ impl Visitor for Interpreter {
         Err(ErrType::Return(value))
     }
 
-    fn visit_print_stmt(&mut self, print_stmt: &PrintStmt) -> Result<Option<Self::R>, Self::E> {
-        let value = self.visit_expr(&print_stmt.expr)?;
-        if let Some(value) = value {
-            println!("{value}");
-        } else {
-            println!("nil");
+    fn visit_import_stmt(&mut self, import_stmt: &ImportStmt) -> Result<Option<Self::R>, Self::E> {
+        let path = module::resolve_import(Path::new(&*import_stmt.keyword.file), import_stmt);
+        let Some(module) = self.modules.get(&path) else {
+            return Err(ErrType::Err(Box::new(import_stmt.path.clone()), format!("Module {} has not been loaded.", import_stmt.path.text)));
+        };
+        let names: Vec<&String> = match &import_stmt.names {
+            Some(names) => names.iter().map(|name| &name.text).collect(),
+            None => module.exports.iter().collect()
+        };
+        for name in names {
+            let value = module.globals.borrow().get_at(0, name.clone());
+            self.environment.borrow_mut().define(name.clone(), value);
         }
         Ok(None)
     }
+
+    fn visit_error_stmt(&mut self, error_stmt: &ErrorStmt) -> Result<Option<Self::R>, Self::E> {
+        Err(ErrType::Err(Box::new(error_stmt.token.clone()), "Cannot execute code that failed to parse.".to_string()))
+    }
+
+    fn visit_print_stmt(&mut self, print_stmt: &PrintStmt) -> Result<Option<Self::R>, Self::E> {
+        let value = self.visit_expr(&print_stmt.expr)?.unwrap_or(Rc::new(Value::Nil));
+        // a closed pipe must not abort the program
+        let _ = writeln!(self.output, "{value}");
+        Ok(None)
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
+        Self::with_output(Output::Stdout)
+    }
+
+    pub fn with_output(output: Output) -> Self {
         let environment = Rc::new(RefCell::new(Environment::new(None)));
+        init_native_functions(environment.clone());
+        let error_class = Rc::new(RefCell::new(Class::new("Error".to_string(), HashMap::new(), None)));
+        error_class.borrow_mut().set_weak_self(Rc::downgrade(&error_class));
         Self { 
             globals: environment.clone(),
+            output,
             environment: environment,
-            locals: HashMap::new()
+            locals: HashMap::new(),
+            modules: HashMap::new(),
+            frames: Vec::new(),
+            call_site: None,
+            heap: Heap::new(),
+            random: Random::new(),
+            error_class
         }
     }
 
-    pub fn interpret(&mut self, stmts: &Vec<Stmt>) {
-        init_native_functions(self.globals.clone());
+    /// Runs `stmts` as the module at `path`, with its own globals so its top-level names stay private.
+    /// Every module imported by `stmts` must have been run before.
+    pub fn interpret_module(&mut self, path: PathBuf, stmts: &[Stmt], exports: Vec<String>) -> Result<(), Diagnostic> {
+        let globals = Rc::new(RefCell::new(Environment::new(None)));
+        init_native_functions(globals.clone());
+        self.modules.insert(path, Module { globals: globals.clone(), exports });
+        let previous = std::mem::replace(&mut self.environment, globals);
+        let result = self.interpret(stmts);
+        self.environment = previous;
+        result
+    }
+
+    pub fn interpret(&mut self, stmts: &[Stmt]) -> Result<(), Diagnostic> {
         for stmt in stmts {
-            if let Err(ErrType::Err(token, message)) = self.visit_stmt(stmt) {
-                println!("Runtime error: {} {} {}", token.start, token.end, message);
+            if let Err(error) = self.visit_stmt(stmt) && let Some(diagnostic) = self.uncaught(error) {
+                return Err(diagnostic);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<Rc<Value>, Diagnostic> {
+        match self.visit_expr(expr) {
+            Ok(value) => Ok(value.unwrap_or(Rc::new(Value::Nil))),
+            Err(error) => match self.uncaught(error) {
+                Some(diagnostic) => Err(diagnostic),
+                None => Ok(Rc::new(Value::Nil))
+            }
+        }
+    }
+
+    /// The diagnostic for an error that reached the top level, or `None` for `return`, `break`
+    /// and `continue`, which the resolver and parser keep inside functions and loops.
+    pub fn uncaught(&mut self, error: ErrType) -> Option<Diagnostic> {
+        match error {
+            ErrType::Err(token, message) => Some(self.runtime_error(&token, message)),
+            ErrType::Throw(token, value) => {
+                // errors raised by the interpreter keep their message when rethrown
+                let message = match &*value {
+                    Value::Instance(instance) => instance.borrow().field("message"),
+                    _ => None
+                };
+                let message = message.map_or_else(|| value.to_string(), |message| message.to_string());
+                Some(self.runtime_error(&token, format!("Uncaught exception: {message}")))
+            }
+            _ => None
+        }
+    }
+
+    /// An `Error` instance describing a runtime error, for `catch` to bind.
+    fn error_instance(&mut self, token: &Token, message: &str) -> Rc<Value> {
+        let instance = self.heap.instance(Instance::new(self.error_class.clone()));
+        instance.borrow_mut().set_field("message", Rc::new(Value::String(message.to_string())));
+        instance.borrow_mut().set_field("file", Rc::new(Value::String(token.file.to_string())));
+        instance.borrow_mut().set_field("line", Rc::new(Value::Number(token.line as f64)));
+        instance.borrow_mut().set_field("column", Rc::new(Value::Number(token.column as f64)));
+        Rc::new(Value::Instance(instance))
+    }
+
+    /// Calls `callee` as a call expression whose `(` is `call_site` would.
+    pub fn call(&mut self, callee: &Value, arguments: Vec<Rc<Value>>, call_site: &Token) -> Result<Rc<Value>, ErrType> {
+        let Some(callable) = callee.as_callable() else {
+            return Err(ErrType::Err(Box::new(call_site.clone()), "Can only call functions and classes.".to_string()));
+        };
+        let (arity, optional_arity) = (callable.borrow().arity(), callable.borrow().optional_arity());
+        if optional_arity > 0 && !(arity..=arity + optional_arity).contains(&arguments.len()) {
+            return Err(ErrType::Err(Box::new(call_site.clone()), format!("Expected {arity} to {} arguments but got {}.", arity + optional_arity, arguments.len())));
+        }
+        if optional_arity == 0 && arguments.len() != arity {
+            return Err(ErrType::Err(Box::new(call_site.clone()), format!("Expected {} arguments but got {}.", arity, arguments.len())));
+        }
+        self.call_site = Some(call_site.clone());
+        // functions and classes push their own frame, natives get one here
+        if let Value::NativeFunction(native_function) = callee {
+            let name = native_function.borrow().get_name();
+            self.push_frame(name);
+            let result = callable.borrow().call(self, arguments);
+            self.leave_frame(&result);
+            return result;
+        }
+        callable.borrow().call(self, arguments)
+    }
+
+    pub fn push_frame(&mut self, name: String) {
+        self.frames.push(CallFrame { name, call_site: self.call_site.clone() });
+    }
+
+    pub fn pop_frame(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Pops the frame of a finished call, unless the call failed: the traceback still needs it then.
+    pub fn leave_frame<T>(&mut self, result: &Result<T, ErrType>) {
+        if !matches!(result, Err(ErrType::Err(..) | ErrType::Throw(..))) {
+            self.pop_frame();
+        }
+    }
+
+    /// An error reported by a native function, located at the call that entered it.
+    pub fn call_site_error(&self, message: String) -> ErrType {
+        ErrType::Err(Box::new(self.call_site()), message)
+    }
+
+    /// The `(` of the call to the running native, where its errors are reported and the
+    /// functions it calls back are called from.
+    pub fn call_site(&self) -> Token {
+        self.frames.last()
+            .and_then(|frame| frame.call_site.clone())
+            .or_else(|| self.call_site.clone())
+            .expect("natives are only called from a call expression")
+    }
+
+    /// Turns an uncaught error into a diagnostic whose notes are the traceback, innermost call first.
+    /// The frames the error unwound through are still on the stack; they are dropped here.
+    pub fn runtime_error(&mut self, token: &Token, message: String) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(Stage::Runtime, message, Span::from(token));
+        let mut location = Some(token.clone());
+        let frames = std::mem::take(&mut self.frames);
+        for (depth, frame) in frames.iter().rev().enumerate() {
+            if depth == MAX_TRACEBACK {
+                diagnostic = diagnostic.with_note(format!("... {} more", frames.len() - MAX_TRACEBACK));
+                location = frames[0].call_site.clone();
                 break;
             }
+            diagnostic = diagnostic.with_note(traceback_entry(&frame.name, location.as_ref()));
+            location = frame.call_site.clone();
         }
+        if !frames.is_empty() {
+            diagnostic = diagnostic.with_note(traceback_entry("<script>", location.as_ref()));
+        }
+        diagnostic
     }
 
     fn is_truthy(&self, value: Rc<Value>) -> bool {
@@ -450,11 +653,153 @@ impl Interpreter {
         *left == *right
     }
 
+    /// Applies the binary `operator`, reporting errors at `op`. The two differ for compound assignments,
+    /// where `op` is the `+=` and `operator` the `+` it stands for.
+    fn binary(&self, op: &Token, operator: &TokenType, lhs: &Rc<Value>, rhs: &Rc<Value>) -> Result<Option<Rc<Value>>, ErrType> {
+        match operator {
+            TokenType::Minus => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() - rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::Slash => {
+                self.check_number_operands(op, lhs, rhs)?;
+                if rhs.as_number().unwrap() == 0.0 {
+                    return Err(ErrType::Err(Box::new(op.clone()), "Cannot divide by 0.".to_string()));
+                }
+                let result = lhs.as_number().unwrap() / rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::Star => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() * rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::Percent => {
+                self.check_number_operands(op, lhs, rhs)?;
+                if rhs.as_number().unwrap() == 0.0 {
+                    return Err(ErrType::Err(Box::new(op.clone()), "Cannot take modulo by 0.".to_string()));
+                }
+                let result = lhs.as_number().unwrap() % rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::TildeSlash => {
+                self.check_number_operands(op, lhs, rhs)?;
+                if rhs.as_number().unwrap() == 0.0 {
+                    return Err(ErrType::Err(Box::new(op.clone()), "Cannot divide by 0.".to_string()));
+                }
+                let result = (lhs.as_number().unwrap() / rhs.as_number().unwrap()).floor();
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::StarStar => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap().powf(rhs.as_number().unwrap());
+                return Ok(Some(Rc::new(Value::Number(result))));
+            },
+            TokenType::Ampersand | TokenType::Pipe | TokenType::Caret => {
+                let (lhs, rhs) = self.integer_operands(op, lhs, rhs)?;
+                let result = match operator {
+                    TokenType::Ampersand => lhs & rhs,
+                    TokenType::Pipe => lhs | rhs,
+                    _ => lhs ^ rhs
+                };
+                return Ok(Some(Rc::new(Value::Number(result as f64))));
+            },
+            TokenType::LessLess | TokenType::GreaterGreater => {
+                let (lhs, rhs) = self.integer_operands(op, lhs, rhs)?;
+                if !(0..64).contains(&rhs) {
+                    return Err(ErrType::Err(Box::new(op.clone()), "Shift amount must be between 0 and 63.".to_string()));
+                }
+                let result = if *operator == TokenType::LessLess { lhs << rhs } else { lhs >> rhs };
+                return Ok(Some(Rc::new(Value::Number(result as f64))));
+            },
+            TokenType::Plus => {
+                if let (Some(lhs), Some(rhs)) = (lhs.as_number(), rhs.as_number()) {
+                    let result = lhs + rhs;
+                    return Ok(Some(Rc::new(Value::Number(result))));
+                } else if let (Some(lhs), Some(rhs)) = (lhs.as_string(), rhs.as_string()) {
+                    let result = lhs + &rhs;
+                    return Ok(Some(Rc::new(Value::String(result))));
+                } else if let (Some(lhs), Some(rhs)) = (lhs.as_number(), rhs.as_string()) {
+                    let result = lhs.to_string() + &rhs;
+                    return Ok(Some(Rc::new(Value::String(result))));
+                } else if let (Some(lhs), Some(rhs)) = (lhs.as_string(), rhs.as_number()) {
+                    let result = lhs + &rhs.to_string();
+                    return Ok(Some(Rc::new(Value::String(result))));
+                } else {
+                    return Err(ErrType::Err(Box::new(op.clone()), "Operands must be two numbers or two strings.".to_string()));
+                }
+            },
+
+            TokenType::Greater => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() > rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Bool(result))));
+            },
+            TokenType::GreaterEqual => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() >= rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Bool(result))));
+            },
+            TokenType::Less => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() < rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Bool(result))));
+            },
+            TokenType::LessEqual => {
+                self.check_number_operands(op, lhs, rhs)?;
+                let result = lhs.as_number().unwrap() <= rhs.as_number().unwrap();
+                return Ok(Some(Rc::new(Value::Bool(result))));
+            },
+
+            TokenType::BangEqual => {
+                return Ok(Some(Rc::new(Value::Bool(!self.is_equal(lhs, rhs)))));
+            },
+            TokenType::EqualEqual => {
+                return Ok(Some(Rc::new(Value::Bool(self.is_equal(lhs, rhs)))));
+            },
+            TokenType::Comma => {
+                return Ok(Some(rhs.clone()));
+            }
+
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    /// Evaluates the right-hand side of an assignment and returns the value to store along with the
+    /// value of the assignment itself. Compound assignments combine `value` with the `current` value
+    /// of their target, which the caller has already read so that the target is only evaluated once.
+    fn assigned_value(&mut self, op: &Option<Token>, postfix: bool, current: Option<Rc<Value>>, value: &Expr) -> Result<(Rc<Value>, Rc<Value>), ErrType> {
+        let value = self.visit_expr(value)?.unwrap_or(Rc::new(Value::Nil));
+        let (Some(op), Some(current)) = (op, current) else {
+            return Ok((value.clone(), value));
+        };
+        let operator = match op.token_type {
+            TokenType::PlusEqual => TokenType::Plus,
+            TokenType::MinusEqual => TokenType::Minus,
+            TokenType::StarEqual => TokenType::Star,
+            TokenType::SlashEqual => TokenType::Slash,
+            TokenType::PercentEqual => TokenType::Percent,
+            TokenType::PlusPlus | TokenType::MinusMinus => {
+                self.check_number_operand(op, &current)?;
+                if op.token_type == TokenType::PlusPlus { TokenType::Plus } else { TokenType::Minus }
+            }
+            _ => unreachable!()
+        };
+        let updated = self.binary(op, &operator, &current, &value)?.unwrap();
+        if postfix {
+            return Ok((updated, current));
+        }
+        Ok((updated.clone(), updated))
+    }
+
     fn check_number_operand(&self, operator: &Token, operand: &Value) -> Result<(), ErrType> {
         if let Value::Number(_) = operand {
             return Ok(());
         }
-        Err(ErrType::Err(operator.clone(), "Operand must be a number.".to_string()))
+        Err(ErrType::Err(Box::new(operator.clone()), "Operand must be a number.".to_string()))
     }
 
     fn check_number_operands(&self, operator: &Token, left: &Value, right: &Value) -> Result<(), ErrType> {
@@ -462,7 +807,117 @@ impl Interpreter {
            self.check_number_operand(operator, right).is_ok() {
             return Ok(());
         }
-        Err(ErrType::Err(operator.clone(), "Operands must be numbers.".to_string()))
+        Err(ErrType::Err(Box::new(operator.clone()), "Operands must be numbers.".to_string()))
+    }
+
+    /// Bitwise operators work on the 64-bit integer a whole number stands for.
+    fn integer_operand(&self, operator: &Token, operand: &Value) -> Result<i64, ErrType> {
+        if let Some(value) = operand.as_number() && value.fract() == 0.0 {
+            return Ok(value as i64);
+        }
+        Err(ErrType::Err(Box::new(operator.clone()), "Operand must be an integer.".to_string()))
+    }
+
+    fn integer_operands(&self, operator: &Token, left: &Value, right: &Value) -> Result<(i64, i64), ErrType> {
+        if let (Ok(left), Ok(right)) = (self.integer_operand(operator, left), self.integer_operand(operator, right)) {
+            return Ok((left, right));
+        }
+        Err(ErrType::Err(Box::new(operator.clone()), "Operands must be integers.".to_string()))
+    }
+
+    fn array_index(&self, index: &Value, bracket: &Token) -> Result<isize, ErrType> {
+        if let Some(index) = index.as_number() && index.fract() == 0.0 {
+            return Ok(index as isize);
+        }
+        Err(ErrType::Err(Box::new(bracket.clone()), "Index must be an integer.".to_string()))
+    }
+
+    fn map_key(&self, key: &Value, token: &Token) -> Result<MapKey, ErrType> {
+        MapKey::from_value(key)
+            .ok_or_else(|| ErrType::Err(Box::new(token.clone()), "Map keys must be strings, numbers, booleans or nil.".to_string()))
+    }
+
+    /// Loops `for_in_stmt` over the elements of `iterable`.
+    fn iterate(&mut self, for_in_stmt: &ForInStmt, iterable: Rc<Value>) -> Result<(), ErrType> {
+        let keyword = &for_in_stmt.keyword;
+        match &*iterable {
+            Value::Array(array) => {
+                // elements pushed by the body are visited too
+                let mut index = 0;
+                loop {
+                    let element = array.borrow().elements.get(index).cloned();
+                    let Some(element) = element else {
+                        break;
+                    };
+                    if !self.execute_for_in_body(for_in_stmt, element)? {
+                        break;
+                    }
+                    index += 1;
+                }
+            }
+            Value::String(string) => {
+                for character in string.chars() {
+                    if !self.execute_for_in_body(for_in_stmt, Rc::new(Value::String(character.to_string())))? {
+                        break;
+                    }
+                }
+            }
+            Value::Map(map) => {
+                let keys = map.borrow().keys();
+                for key in keys {
+                    if !self.execute_for_in_body(for_in_stmt, key)? {
+                        break;
+                    }
+                }
+            }
+            Value::Instance(_) => {
+                // `iter()` is optional; an object with `next()` is its own iterator
+                let iterator = match self.method(&iterable, "iter", keyword) {
+                    Some(iter) => self.call(&iter, Vec::new(), keyword)?,
+                    None => iterable.clone()
+                };
+                if !matches!(*iterator, Value::Instance(_)) {
+                    return self.iterate(for_in_stmt, iterator);
+                }
+                let Some(next) = self.method(&iterator, "next", keyword) else {
+                    return Err(ErrType::Err(Box::new(keyword.clone()), "Iterators must have a 'next' method.".to_string()));
+                };
+                loop {
+                    let value = self.call(&next, Vec::new(), keyword)?;
+                    if let Value::Nil = *value {
+                        break;
+                    }
+                    if !self.execute_for_in_body(for_in_stmt, value)? {
+                        break;
+                    }
+                }
+            }
+            _ => return Err(ErrType::Err(Box::new(keyword.clone()), "Can only loop over arrays, strings, maps and iterators.".to_string()))
+        }
+        Ok(())
+    }
+
+    /// Runs one iteration of `for_in_stmt` with its variable bound to `value`. Returns `false` on `break`.
+    fn execute_for_in_body(&mut self, for_in_stmt: &ForInStmt, value: Rc<Value>) -> Result<bool, ErrType> {
+        let environment = self.heap.environment(Some(self.environment.clone()));
+        environment.borrow_mut().define(for_in_stmt.name.text.clone(), value);
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.visit_stmt(&for_in_stmt.body);
+        self.environment = previous;
+        match result {
+            Ok(_) | Err(ErrType::Continue(_)) => Ok(true),
+            Err(ErrType::Break) => Ok(false),
+            Err(error) => Err(error)
+        }
+    }
+
+    /// The property `name` of `object` when it is an instance that has one.
+    fn method(&self, object: &Value, name: &str, token: &Token) -> Option<Rc<Value>> {
+        let Value::Instance(instance) = object else {
+            return None;
+        };
+        let name = Token { text: name.to_string(), ..token.clone() };
+        instance.borrow().get(&name).ok()
     }
 
     pub fn execute_block(&mut self, stmts: &Vec<Stmt>, environment: Rc<RefCell<Environment>>) -> Result<(), ErrType> {
@@ -495,7 +950,8 @@ impl Interpreter {
         if let Some(distance) = distance {
             Ok(Some(self.environment.borrow().get_at(*distance, name.text.clone())))
         } else {
-            let variable = self.globals.borrow().get(name);
+            // unresolved names are globals of the module the code was declared in
+            let variable = Environment::root(&self.environment).borrow().get(name);
             if let Err((token, message)) = variable {
                 Err(ErrType::Err(token, message))
             } else if let Ok(variable) = variable {
@@ -508,6 +964,13 @@ impl Interpreter {
     }
 }
 
+fn traceback_entry(name: &str, location: Option<&Token>) -> String {
+    match location {
+        Some(token) => format!("in {name} at {}:{}", token.file, token.line),
+        None => format!("in {name}")
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub enum Value {
     Bool(bool),
@@ -518,6 +981,7 @@ pub enum Value {
     Class(Rc<RefCell<Class>>),
     Instance(Rc<RefCell<Instance>>),
     Array(Rc<RefCell<Array>>),
+    Map(Rc<RefCell<Map>>),
     Nil
 }
 
@@ -526,6 +990,7 @@ impl Value {
         match literal {
             Literal::Bool(value) => Self::Bool(value.clone()),
             Literal::String(value) => Self::String(value.clone()),
+            // the scanner reports malformed numbers, so every literal that reaches here parses
             Literal::Number(value) => Self::Number(value.parse().unwrap()),
             Literal::Nil => Self::Nil,
         }
@@ -571,14 +1036,23 @@ impl Value {
             _ => None
         }
     }
+}
 
-    fn as_array(&self) -> Option<Rc<RefCell<Array>>> {
-        if let Self::Array(array) = self {
-            Some(array.clone())
-        } else {
-            None
-        }
+thread_local! {
+    // the arrays and maps being printed, outermost first
+    static PRINTING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Writes a container with `write`, or `placeholder` when it is already being written further out,
+/// so that an array or map that contains itself prints instead of recursing forever.
+fn write_container(f: &mut fmt::Formatter<'_>, address: usize, placeholder: &str, write: impl FnOnce(&mut fmt::Formatter<'_>) -> fmt::Result) -> fmt::Result {
+    if PRINTING.with_borrow(|printing| printing.contains(&address)) {
+        return write!(f, "{placeholder}");
     }
+    PRINTING.with_borrow_mut(|printing| printing.push(address));
+    let result = write(f);
+    PRINTING.with_borrow_mut(|printing| printing.pop());
+    result
 }
 
 impl fmt::Display for Value {
@@ -591,14 +1065,27 @@ impl fmt::Display for Value {
             Self::NativeFunction(_) => write!(f, "<native fn>"),
             Self::Class(class) => write!(f, "{}", class.borrow().to_string()),
             Self::Instance(instance) => write!(f, "{}", instance.borrow().to_string()),
-            Self::Array(array) => write!(f, "{}", array.borrow().to_string()),
+            Self::Array(array) => write_container(f, Rc::as_ptr(array) as usize, "[...]", |f| write!(f, "{}", array.borrow().to_string())),
+            Self::Map(map) => write_container(f, Rc::as_ptr(map) as usize, "{...}", |f| write!(f, "{}", map.borrow())),
             Self::Nil => write!(f, "nil")
         }
     }
 }
 
+// strings are quoted, so embedders can tell `"1"` from `1` in assertions
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Self::String(value) => write!(f, "{value:?}"),
+            _ => write!(f, "{self}")
+        }
+    }
+}
+
 pub enum ErrType {
-    Err(Token, String),
+    Err(Box<Token>, String),
+    // a value raised by `throw`, located at the keyword
+    Throw(Box<Token>, Rc<Value>),
     Return(Rc<Value>),
     Break,
     Continue(Rc<RefCell<Environment>>)
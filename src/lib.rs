@@ -1,8 +1,14 @@
+pub mod lox;
+pub mod output;
 pub mod project;
+pub mod module;
+pub mod repl;
+pub mod tester;
 pub mod scanner;
 pub mod token;
 pub mod parser;
 pub mod error;
+pub mod diagnostic;
 pub mod ast;
 pub mod visit;
 pub mod resolver;
@@ -13,4 +19,9 @@ pub mod function;
 pub mod class;
 pub mod instance;
 pub mod native;
-pub mod array;
\ No newline at end of file
+pub mod array;
+pub mod map;
+pub mod heap;
+pub mod random;
+
+pub use lox::Lox;
\ No newline at end of file
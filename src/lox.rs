@@ -0,0 +1,131 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use crate::{
+    ast::stmt::Stmt,
+    diagnostic::{Diagnostic, Diagnostics},
+    interpreter::{Interpreter, Value},
+    native::host::HostFunction,
+    output::Output,
+    parser::Parser,
+    resolver::Resolver,
+    scanner::Scanner,
+    token::{Token, TokenType}
+};
+
+// file name of the synthetic call site used by `Lox::call`
+const HOST_FILE: &str = "<host>";
+
+/// Runs Lox source from Rust. Globals live as long as the `Lox` value, so functions defined
+/// by one `eval` can be used by the next one or called from Rust with `call`.
+pub struct Lox {
+    interpreter: Rc<RefCell<Interpreter>>,
+    resolver: Resolver,
+    // every `eval` is scanned as its own file `<eval:N>`, kept around for rendering diagnostics
+    sources: HashMap<String, String>
+}
+
+impl Lox {
+    pub fn new() -> Self {
+        Self::with_output(Output::Stdout)
+    }
+
+    /// `print` writes to `output`. Errors are not written anywhere; `eval` and `call` return them.
+    pub fn with_output(output: Output) -> Self {
+        let interpreter = Rc::new(RefCell::new(Interpreter::with_output(output)));
+        Self {
+            resolver: Resolver::new(interpreter.clone()),
+            interpreter,
+            sources: HashMap::new()
+        }
+    }
+
+    /// Runs `source` and returns the value of its last statement when that is an expression, `nil` otherwise.
+    pub fn eval(&mut self, source: &str) -> Result<Value, Diagnostics> {
+        let file = format!("<eval:{}>", self.sources.len() + 1);
+        self.sources.insert(file.clone(), source.to_string());
+
+        let mut scanner = Scanner::new(file.clone(), source.to_string());
+        let tokens = scanner.scan_tokens();
+        if *scanner.had_error.borrow() {
+            return Err(self.diagnostics(scanner.diagnostics.take()));
+        }
+        let mut parse_result = Parser::new(tokens).parse();
+        if parse_result.has_errors() {
+            // allow a trailing expression without `;`, as in `x + 1`
+            let mut scanner = Scanner::new(file, format!("{};", source.trim_end()));
+            let retry = Parser::new(scanner.scan_tokens()).parse();
+            if !retry.has_errors() {
+                parse_result = retry;
+            }
+        }
+        if parse_result.has_errors() {
+            return Err(self.diagnostics(parse_result.diagnostics));
+        }
+        self.resolver.resolve(&parse_result.stmts);
+        let diagnostics = self.resolver.diagnostics.take();
+        if self.resolver.had_error.replace(false) {
+            return Err(self.diagnostics(diagnostics));
+        }
+
+        let mut interpreter = self.interpreter.borrow_mut();
+        let result = match parse_result.stmts.split_last() {
+            Some((Stmt::Expr(expr_stmt), stmts)) => interpreter.interpret(stmts)
+                .and_then(|_| interpreter.evaluate(&expr_stmt.expr))
+                .map(|value| (*value).clone()),
+            _ => interpreter.interpret(&parse_result.stmts).map(|_| Value::Nil)
+        };
+        drop(interpreter);
+        result.map_err(|diagnostic| self.diagnostics(vec![diagnostic]))
+    }
+
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        self.interpreter.borrow().globals.borrow_mut().define(name.to_string(), Rc::new(value));
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.interpreter.borrow().globals.borrow().get_own(name).map(|value| (*value).clone())
+    }
+
+    /// Makes a Rust closure callable from Lox as `name`. Returning `Err(message)` raises a runtime error.
+    pub fn define_function(&mut self, name: &str, arity: usize, function: impl Fn(&[Value]) -> Result<Value, String> + 'static) {
+        let function = HostFunction::new(name.to_string(), arity, function);
+        self.set_global(name, Value::NativeFunction(Rc::new(RefCell::new(function))));
+    }
+
+    /// Calls the global function, class or native `name` with `arguments`.
+    pub fn call(&mut self, name: &str, arguments: Vec<Value>) -> Result<Value, Diagnostics> {
+        let call_site = Token {
+            text: name.to_string(),
+            file: Rc::from(HOST_FILE),
+            start: 0,
+            end: name.len().saturating_sub(1),
+            line: 1,
+            column: 1,
+            token_type: TokenType::Identifier,
+            literal: None
+        };
+        let callee = self.interpreter.borrow().globals.borrow().get(&call_site);
+        let result = match callee {
+            Ok(callee) => {
+                let arguments = arguments.into_iter().map(Rc::new).collect();
+                let mut interpreter = self.interpreter.borrow_mut();
+                match interpreter.call(&callee, arguments, &call_site) {
+                    Ok(value) => Ok((*value).clone()),
+                    // `return`, `break` and `continue` never escape a call
+                    Err(error) => interpreter.uncaught(error).map_or(Ok(Value::Nil), Err)
+                }
+            }
+            Err((token, message)) => Err(self.interpreter.borrow_mut().runtime_error(&token, message))
+        };
+        result.map_err(|diagnostic| self.diagnostics(vec![diagnostic]))
+    }
+
+    fn diagnostics(&self, diagnostics: Vec<Diagnostic>) -> Diagnostics {
+        Diagnostics::new(diagnostics, |file| self.sources.get(file).map_or("", |source| source.as_str()))
+    }
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
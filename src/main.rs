@@ -1,10 +1,217 @@
-use std::{env, path::PathBuf};
-use rust_lox::project::Project;
+use std::{env, fs, path::PathBuf, process};
+use rust_lox::{ast::printer::AstPrinter, diagnostic::ErrorFormat, parser::Parser, project::{Project, Status}, repl::Repl, scanner::Scanner, tester::Tester};
 
+// exit codes from sysexits.h, as used by the reference implementation
+const EX_USAGE: i32 = 64;
+const EX_DATAERR: i32 = 65;
+const EX_NOINPUT: i32 = 66;
+const EX_SOFTWARE: i32 = 70;
+const EX_IOERR: i32 = 74;
+
+const USAGE: &str = "\
+Usage: rust-lox [options] [command] [args]
+
+Commands:
+  run <path>      Run a .lox file, or every .lox file under a directory
+  check <path>    Scan, parse and resolve without running
+  tokens <file>   Print the tokens produced by the scanner
+  ast <file>      Print the parsed syntax tree
+  test <path>...  Run annotated test files and compare their output
+  repl            Start an interactive prompt (the default with no command)
+
+Options:
+  --error-format=human|json   How diagnostics are written to stderr
+  --entry=<file>              File that `run` executes first in a directory
+  --include=<glob>            Only collect files matching the glob (repeatable)
+  --exclude=<glob>            Skip files matching the glob (repeatable)
+  --known-failures=<file>     Files for `test` to skip, one per line
+  -h, --help                  Print this help
+
+`rust-lox <path>` is shorthand for `rust-lox run <path>`.";
+
+struct Options {
+    error_format: ErrorFormat,
+    known_failures: Option<PathBuf>,
+    entry: Option<PathBuf>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    args: Vec<String>
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let mut project = Project::new(PathBuf::from(args[1].clone()));
+    let options = parse_options();
+    let mut args = options.args.iter().map(|arg| arg.as_str());
+    let code = match args.next() {
+        None | Some("repl") => repl(),
+        Some("run") => run(&options, args.next(), false),
+        Some("check") => run(&options, args.next(), true),
+        Some("tokens") => tokens(args.next()),
+        Some("ast") => ast(args.next()),
+        Some("test") => test(&options, args.collect()),
+        Some(path) if options.args.len() == 1 && !path.starts_with('-') => run(&options, Some(path), false),
+        Some(command) => usage_error(&format!("Unknown command '{command}'."))
+    };
+    process::exit(code);
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        error_format: ErrorFormat::Human,
+        known_failures: None,
+        entry: None,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        args: Vec::new()
+    };
+    for arg in env::args().skip(1) {
+        if arg == "-h" || arg == "--help" {
+            println!("{USAGE}");
+            process::exit(0);
+        } else if let Some(format) = arg.strip_prefix("--error-format=") {
+            options.error_format = match format {
+                "human" => ErrorFormat::Human,
+                "json" => ErrorFormat::Json,
+                _ => process::exit(usage_error(&format!("Unknown error format '{format}', expected 'human' or 'json'.")))
+            };
+        } else if let Some(path) = arg.strip_prefix("--known-failures=") {
+            options.known_failures = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--entry=") {
+            options.entry = Some(PathBuf::from(path));
+        } else if let Some(pattern) = arg.strip_prefix("--include=") {
+            options.include.push(pattern.to_string());
+        } else if let Some(pattern) = arg.strip_prefix("--exclude=") {
+            options.exclude.push(pattern.to_string());
+        } else if arg.starts_with("--") {
+            process::exit(usage_error(&format!("Unknown option '{arg}'.")));
+        } else {
+            options.args.push(arg);
+        }
+    }
+    options
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("{message}\n\n{USAGE}");
+    EX_USAGE
+}
+
+fn repl() -> i32 {
+    if let Err(error) = Repl::new().run() {
+        eprintln!("{error}");
+        return EX_IOERR;
+    }
+    0
+}
+
+fn run(options: &Options, path: Option<&str>, check_only: bool) -> i32 {
+    let Some(path) = path else {
+        return usage_error("Missing path.");
+    };
+    let mut project = Project::new(PathBuf::from(path));
+    project.error_format = options.error_format;
+    project.entry = options.entry.clone();
+    for pattern in &options.include {
+        if let Err(message) = project.include(pattern) {
+            return usage_error(&message);
+        }
+    }
+    for pattern in &options.exclude {
+        if let Err(message) = project.exclude(pattern) {
+            return usage_error(&message);
+        }
+    }
     project.collect_files();
-    project.compile();
-}
\ No newline at end of file
+    if project.files.is_empty() {
+        eprintln!("No .lox files found at '{path}'.");
+        return EX_NOINPUT;
+    }
+    if let Some(entry) = &project.entry && project.entry_file().is_none() {
+        eprintln!("Entry file '{}' is not part of '{path}'.", entry.display());
+        return EX_NOINPUT;
+    }
+    let status = if check_only {
+        project.check()
+    } else {
+        project.compile()
+    };
+    match status {
+        Status::Ok => 0,
+        Status::CompileError => EX_DATAERR,
+        Status::RuntimeError => EX_SOFTWARE
+    }
+}
+
+fn read_source(path: Option<&str>) -> Result<(String, String), i32> {
+    let Some(path) = path else {
+        return Err(usage_error("Missing file."));
+    };
+    match fs::read_to_string(path) {
+        Ok(source) => Ok((path.to_string(), source)),
+        Err(error) => {
+            eprintln!("Cannot read '{path}': {error}");
+            Err(EX_NOINPUT)
+        }
+    }
+}
+
+fn tokens(path: Option<&str>) -> i32 {
+    let (path, source) = match read_source(path) {
+        Ok(file) => file,
+        Err(code) => return code
+    };
+    let mut scanner = Scanner::new(path, source.clone());
+    for token in scanner.scan_tokens() {
+        let literal = token.literal.map_or(String::new(), |literal| format!(" {literal:?}"));
+        println!("{}:{} {:?} {}{}", token.line, token.column, token.token_type, token.text, literal);
+    }
+    for diagnostic in scanner.diagnostics.borrow().iter() {
+        eprintln!("{}", diagnostic.render(&source));
+    }
+    if *scanner.had_error.borrow() {
+        return EX_DATAERR;
+    }
+    0
+}
+
+fn ast(path: Option<&str>) -> i32 {
+    let (path, source) = match read_source(path) {
+        Ok(file) => file,
+        Err(code) => return code
+    };
+    let mut scanner = Scanner::new(path, source.clone());
+    let tokens = scanner.scan_tokens();
+    let parse_result = Parser::new(tokens).parse();
+    println!("{}", AstPrinter::print(&parse_result.stmts));
+    for diagnostic in scanner.diagnostics.borrow().iter().chain(parse_result.diagnostics.iter()) {
+        eprintln!("{}", diagnostic.render(&source));
+    }
+    if *scanner.had_error.borrow() || parse_result.has_errors() {
+        return EX_DATAERR;
+    }
+    0
+}
+
+fn test(options: &Options, paths: Vec<&str>) -> i32 {
+    if paths.is_empty() {
+        return usage_error("Missing test path.");
+    }
+    let executable = match env::current_exe() {
+        Ok(executable) => executable,
+        Err(error) => {
+            eprintln!("Cannot locate the rust-lox executable: {error}");
+            return EX_SOFTWARE;
+        }
+    };
+    let mut tester = Tester::new(executable);
+    if let Some(known_failures) = &options.known_failures {
+        tester = tester.with_known_failures(known_failures);
+    }
+    let roots: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    let summary = tester.run(&roots);
+    println!("{}", summary.report());
+    if summary.failures.is_empty() {
+        0
+    } else {
+        1
+    }
+}
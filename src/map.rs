@@ -0,0 +1,111 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+use crate::interpreter::Value;
+
+/// Only immutable, comparable values can be map keys.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Bool(bool),
+    String(String),
+    // the bits of the number, with -0 folded into 0 so both find the same entry
+    Number(u64),
+    Nil
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(Self::Bool(*value)),
+            Value::String(value) => Some(Self::String(value.clone())),
+            Value::Number(value) => Some(Self::Number(if *value == 0.0 { 0.0f64.to_bits() } else { value.to_bits() })),
+            Value::Nil => Some(Self::Nil),
+            _ => None
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Bool(value) => Value::Bool(*value),
+            Self::String(value) => Value::String(value.clone()),
+            Self::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            Self::Nil => Value::Nil
+        }
+    }
+}
+
+/// A dictionary that remembers insertion order; overwriting a key keeps its original position.
+pub struct Map {
+    keys: Vec<MapKey>,
+    values: HashMap<MapKey, Rc<Value>>
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: HashMap::new()
+        }
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<Rc<Value>> {
+        self.values.get(key).cloned()
+    }
+
+    pub fn set(&mut self, key: MapKey, value: Rc<Value>) {
+        if self.values.insert(key.clone(), value).is_none() {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn has(&self, key: &MapKey) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Rc<Value>> {
+        let value = self.values.remove(key)?;
+        self.keys.retain(|existing| existing != key);
+        Some(value)
+    }
+
+    pub fn keys(&self) -> Vec<Rc<Value>> {
+        self.keys.iter().map(|key| Rc::new(key.to_value())).collect()
+    }
+
+    pub fn values(&self) -> Vec<Rc<Value>> {
+        self.keys.iter().map(|key| self.values[key].clone()).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// maps are equal only to themselves, so comparing a map that contains itself terminates
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self.keys.iter()
+            .map(|key| format!("{}: {}", key.to_value(), self.values[key]))
+            .collect();
+        write!(f, "{{{}}}", entries.join(", "))
+    }
+}
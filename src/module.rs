@@ -0,0 +1,47 @@
+use std::{cell::RefCell, path::{Component, Path, PathBuf}, rc::Rc};
+use crate::{ast::stmt::*, environment::Environment, token::Literal};
+
+/// A file that has been executed. Its top-level declarations live in `globals`,
+/// so they never leak into other modules; only the names in `exports` can be imported.
+pub struct Module {
+    pub globals: Rc<RefCell<Environment>>,
+    pub exports: Vec<String>
+}
+
+/// Path of the module named by `import_stmt`, relative to the directory of the importing file.
+pub fn resolve_import(importer: &Path, import_stmt: &ImportStmt) -> PathBuf {
+    let target = match &import_stmt.path.literal {
+        Some(Literal::String(target)) => target.as_str(),
+        _ => ""
+    };
+    normalize(&importer.parent().unwrap_or(Path::new("")).join(target))
+}
+
+/// Removes `.` and `..` components without touching the file system,
+/// so every spelling of a module path maps to the same module.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(result.components().next_back(), Some(Component::Normal(_))) => {
+                result.pop();
+            }
+            component => result.push(component)
+        }
+    }
+    result
+}
+
+pub fn imports(stmts: &[Stmt]) -> Vec<&ImportStmt> {
+    stmts.iter()
+        .filter_map(|stmt| if let Stmt::Import(import_stmt) = stmt { Some(import_stmt) } else { None })
+        .collect()
+}
+
+pub fn exports(stmts: &[Stmt]) -> Vec<String> {
+    stmts.iter()
+        .filter_map(|stmt| if let Stmt::Export(export_stmt) = stmt { export_stmt.name() } else { None })
+        .map(|name| name.text.clone())
+        .collect()
+}
@@ -1,5 +1,5 @@
 use std::rc::Rc;
-use crate::{callable::Callable, interpreter::{Interpreter, Value}, native::NativeFunction};
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::{MAX_LENGTH, NativeFunction, array_argument, function_argument, integer_argument}};
 
 pub struct ArrayPush {}
 
@@ -10,15 +10,14 @@ impl NativeFunction for ArrayPush {
 }
 
 impl Callable for ArrayPush {
-    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value> {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
         let array = &arguments[0];
         if let Value::Array(array) = &**array {
             let value = &arguments[1];
             array.borrow_mut().push(value.clone());
-        } else {
-            println!("Only arrays can be pushed.");
+            return Ok(Rc::new(Value::Nil));
         }
-        Rc::new(Value::Nil)
+        Err(interpreter.call_site_error("Only arrays can be pushed.".to_string()))
     }
 
     fn arity(&self) -> usize {
@@ -35,22 +34,370 @@ impl NativeFunction for ArrayPop {
 }
 
 impl Callable for ArrayPop {
-    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value> {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
         let array = &arguments[0];
         if let Value::Array(array) = &**array {
             let value = array.borrow_mut().pop();
             if let Some(value) = value {
-                return value;
-            } else {
-                println!("Failed to pop from array.");
+                return Ok(value);
+            }
+            return Err(interpreter.call_site_error("Cannot pop from an empty array.".to_string()));
+        }
+        Err(interpreter.call_site_error("Only arrays can be popped.".to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+// Natives that call back into Lox work on a copy of the elements, so callbacks may modify the array
+// they are given. Unless they say otherwise, they leave the array alone and return a new one.
+
+/// The index `i` refers to in an array of `length` elements, counting from the end when negative,
+/// if it lies in `0..=length`.
+fn position(i: i64, length: usize) -> Option<usize> {
+    let i = if i < 0 { i + length as i64 } else { i };
+    (0..=length as i64).contains(&i).then_some(i as usize)
+}
+
+fn new_array(interpreter: &mut Interpreter, elements: Vec<Rc<Value>>) -> Rc<Value> {
+    Rc::new(Value::Array(interpreter.heap.array(elements)))
+}
+
+/// `map(array, f)`: `f` applied to each element.
+pub struct ArrayMap {}
+
+impl NativeFunction for ArrayMap {
+    fn get_name(&self) -> String {
+        "map".to_string()
+    }
+}
+
+impl Callable for ArrayMap {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let function = function_argument(interpreter, &arguments, 1)?;
+        let call_site = interpreter.call_site();
+        let elements = array.borrow().elements.clone();
+        let mut results = Vec::new();
+        for element in elements {
+            results.push(interpreter.call(&function, vec![element], &call_site)?);
+        }
+        Ok(new_array(interpreter, results))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// `filter(array, f)`: the elements for which `f` returns a truthy value.
+pub struct ArrayFilter {}
+
+impl NativeFunction for ArrayFilter {
+    fn get_name(&self) -> String {
+        "filter".to_string()
+    }
+}
+
+impl Callable for ArrayFilter {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let function = function_argument(interpreter, &arguments, 1)?;
+        let call_site = interpreter.call_site();
+        let elements = array.borrow().elements.clone();
+        let mut results = Vec::new();
+        for element in elements {
+            let keep = interpreter.call(&function, vec![element.clone()], &call_site)?;
+            if !matches!(*keep, Value::Nil | Value::Bool(false)) {
+                results.push(element);
             }
+        }
+        Ok(new_array(interpreter, results))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// `reduce(array, f, initial)`: folds the elements with `f(accumulator, element)`. Without an
+/// initial value the first element is used, and an empty array is an error.
+pub struct ArrayReduce {}
+
+impl NativeFunction for ArrayReduce {
+    fn get_name(&self) -> String {
+        "reduce".to_string()
+    }
+}
+
+impl Callable for ArrayReduce {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let function = function_argument(interpreter, &arguments, 1)?;
+        let call_site = interpreter.call_site();
+        let mut elements = array.borrow().elements.clone().into_iter();
+        let Some(mut accumulator) = arguments.get(2).cloned().or_else(|| elements.next()) else {
+            return Err(interpreter.call_site_error("Cannot reduce an empty array without an initial value.".to_string()));
+        };
+        for element in elements {
+            accumulator = interpreter.call(&function, vec![accumulator, element], &call_site)?;
+        }
+        Ok(accumulator)
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn optional_arity(&self) -> usize {
+        1
+    }
+}
+
+pub struct ArrayForEach {}
+
+impl NativeFunction for ArrayForEach {
+    fn get_name(&self) -> String {
+        "for_each".to_string()
+    }
+}
+
+impl Callable for ArrayForEach {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let function = function_argument(interpreter, &arguments, 1)?;
+        let call_site = interpreter.call_site();
+        let elements = array.borrow().elements.clone();
+        for element in elements {
+            interpreter.call(&function, vec![element], &call_site)?;
+        }
+        Ok(Rc::new(Value::Nil))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// `sort(array, compare)`: a stable sort. `compare(a, b)` returns a negative number when `a` goes
+/// before `b`, a positive one when it goes after, and 0 otherwise. Without it, the array must hold
+/// only numbers or only strings, which are sorted in ascending order.
+pub struct ArraySort {}
+
+impl NativeFunction for ArraySort {
+    fn get_name(&self) -> String {
+        "sort".to_string()
+    }
+}
+
+impl Callable for ArraySort {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let elements = array.borrow().elements.clone();
+        let sorted = if arguments.len() > 1 {
+            let function = function_argument(interpreter, &arguments, 1)?;
+            let call_site = interpreter.call_site();
+            merge_sort(elements, &mut |a, b| {
+                let order = interpreter.call(&function, vec![a.clone(), b.clone()], &call_site)?;
+                match *order {
+                    Value::Number(order) => Ok(order > 0.0),
+                    _ => Err(interpreter.call_site_error("Comparator must return a number.".to_string()))
+                }
+            })?
         } else {
-            println!("Only arrays can be poped.");
+            merge_sort(elements, &mut |a, b| match (&**a, &**b) {
+                (Value::Number(a), Value::Number(b)) => Ok(a > b),
+                (Value::String(a), Value::String(b)) => Ok(a > b),
+                _ => Err(interpreter.call_site_error("Can only sort numbers or strings without a comparator.".to_string()))
+            })?
+        };
+        Ok(new_array(interpreter, sorted))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn optional_arity(&self) -> usize {
+        1
+    }
+}
+
+/// Sorts with `after(a, b)`, which says whether `a` belongs after `b`. Written out rather than
+/// using `sort_by`, which may panic when a Lox comparator is not a consistent order.
+fn merge_sort(
+    mut elements: Vec<Rc<Value>>,
+    after: &mut impl FnMut(&Rc<Value>, &Rc<Value>) -> Result<bool, ErrType>
+) -> Result<Vec<Rc<Value>>, ErrType> {
+    if elements.len() <= 1 {
+        return Ok(elements);
+    }
+    let right = elements.split_off(elements.len() / 2);
+    let left = merge_sort(elements, after)?;
+    let right = merge_sort(right, after)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut left, mut right) = (left.into_iter().peekable(), right.into_iter().peekable());
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // taking from the left on ties keeps the sort stable
+        if after(a, b)? {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+pub struct ArrayReverse {}
+
+impl NativeFunction for ArrayReverse {
+    fn get_name(&self) -> String {
+        "reverse".to_string()
+    }
+}
+
+impl Callable for ArrayReverse {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let reversed = array.borrow().elements.iter().rev().cloned().collect();
+        Ok(new_array(interpreter, reversed))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// `slice(array, start, end)`: the elements from `start` up to but not including `end`.
+/// Negative positions count from the end.
+pub struct ArraySlice {}
+
+impl NativeFunction for ArraySlice {
+    fn get_name(&self) -> String {
+        "slice".to_string()
+    }
+}
+
+impl Callable for ArraySlice {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let start = integer_argument(interpreter, &arguments, 1)?;
+        let end = integer_argument(interpreter, &arguments, 2)?;
+        let length = array.borrow().len();
+        let (Some(start), Some(end)) = (position(start, length), position(end, length)) else {
+            return Err(interpreter.call_site_error("Slice out of range.".to_string()));
+        };
+        if start > end {
+            return Err(interpreter.call_site_error("Slice out of range.".to_string()));
+        }
+        let elements = array.borrow().elements[start..end].to_vec();
+        Ok(new_array(interpreter, elements))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+}
+
+pub struct ArrayConcat {}
+
+impl NativeFunction for ArrayConcat {
+    fn get_name(&self) -> String {
+        "concat".to_string()
+    }
+}
+
+impl Callable for ArrayConcat {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let first = array_argument(interpreter, &arguments, 0)?;
+        let second = array_argument(interpreter, &arguments, 1)?;
+        let mut elements = first.borrow().elements.clone();
+        elements.extend(second.borrow().elements.iter().cloned());
+        Ok(new_array(interpreter, elements))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// `insert(array, index, value)` moves the elements from `index` on up to make room, in place.
+/// An index equal to the length appends.
+pub struct ArrayInsert {}
+
+impl NativeFunction for ArrayInsert {
+    fn get_name(&self) -> String {
+        "insert".to_string()
+    }
+}
+
+impl Callable for ArrayInsert {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let index = integer_argument(interpreter, &arguments, 1)?;
+        let Some(index) = position(index, array.borrow().len()) else {
+            return Err(interpreter.call_site_error("Index out of range.".to_string()));
+        };
+        array.borrow_mut().elements.insert(index, arguments[2].clone());
+        Ok(Rc::new(Value::Nil))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+}
+
+/// `remove_at(array, index)` removes an element in place and returns it.
+pub struct ArrayRemoveAt {}
+
+impl NativeFunction for ArrayRemoveAt {
+    fn get_name(&self) -> String {
+        "remove_at".to_string()
+    }
+}
+
+impl Callable for ArrayRemoveAt {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let index = integer_argument(interpreter, &arguments, 1)?;
+        let length = array.borrow().len();
+        match position(index, length) {
+            Some(index) if index < length => Ok(array.borrow_mut().elements.remove(index)),
+            _ => Err(interpreter.call_site_error("Index out of range.".to_string()))
         }
-        Rc::new(Value::Nil)
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// `range(n)`: the integers from 0 up to but not including `n`.
+pub struct Range {}
+
+impl NativeFunction for Range {
+    fn get_name(&self) -> String {
+        "range".to_string()
+    }
+}
+
+impl Callable for Range {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let n = integer_argument(interpreter, &arguments, 0)?;
+        if n < 0 {
+            return Err(interpreter.call_site_error("Range length must not be negative.".to_string()));
+        }
+        if n as usize > MAX_LENGTH {
+            return Err(interpreter.call_site_error("Range is too long.".to_string()));
+        }
+        let elements = (0..n).map(|i| Rc::new(Value::Number(i as f64))).collect();
+        Ok(new_array(interpreter, elements))
     }
 
     fn arity(&self) -> usize {
         1
     }
-}
\ No newline at end of file
+}
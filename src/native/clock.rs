@@ -1,5 +1,5 @@
 use std::{rc::Rc, time::{SystemTime, UNIX_EPOCH}};
-use crate::{callable::Callable, interpreter::{Value, Interpreter}, native::NativeFunction};
+use crate::{callable::Callable, interpreter::{ErrType, Value, Interpreter}, native::NativeFunction};
 
 pub struct Clock {}
 impl NativeFunction for Clock {
@@ -9,9 +9,9 @@ impl NativeFunction for Clock {
 }
 
 impl Callable for Clock {
-    fn call(&self, _interpreter: &mut Interpreter, _arguments: Vec<Rc<Value>>) -> Rc<Value> {
+    fn call(&self, _interpreter: &mut Interpreter, _arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
         let time = SystemTime::now().duration_since(UNIX_EPOCH).expect("").as_secs_f64();
-        Rc::new(Value::Number(time))
+        Ok(Rc::new(Value::Number(time)))
     }
 
     fn arity(&self) -> usize {
@@ -0,0 +1,41 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::NativeFunction};
+
+/// Collects garbage cycles right away and returns how many objects were freed.
+pub struct Gc {}
+
+impl NativeFunction for Gc {
+    fn get_name(&self) -> String {
+        "gc".to_string()
+    }
+}
+
+impl Callable for Gc {
+    fn call(&self, interpreter: &mut Interpreter, _arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let freed = interpreter.heap.collect();
+        Ok(Rc::new(Value::Number(freed as f64)))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+}
+
+/// Number of environments, instances, arrays and maps that are alive.
+pub struct HeapSize {}
+
+impl NativeFunction for HeapSize {
+    fn get_name(&self) -> String {
+        "heap_size".to_string()
+    }
+}
+
+impl Callable for HeapSize {
+    fn call(&self, interpreter: &mut Interpreter, _arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        Ok(Rc::new(Value::Number(interpreter.heap.size() as f64)))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+}
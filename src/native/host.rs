@@ -0,0 +1,42 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::NativeFunction};
+
+type HostClosure = dyn Fn(&[Value]) -> Result<Value, String>;
+
+/// A native backed by a Rust closure, so embedders can expose host functions without writing a new type.
+/// An `Err` from the closure becomes a runtime error at the call site.
+pub struct HostFunction {
+    name: String,
+    arity: usize,
+    function: Box<HostClosure>
+}
+
+impl HostFunction {
+    pub fn new(name: String, arity: usize, function: impl Fn(&[Value]) -> Result<Value, String> + 'static) -> Self {
+        Self {
+            name,
+            arity,
+            function: Box::new(function)
+        }
+    }
+}
+
+impl NativeFunction for HostFunction {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Callable for HostFunction {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let arguments: Vec<Value> = arguments.iter().map(|argument| (**argument).clone()).collect();
+        match (self.function)(&arguments) {
+            Ok(value) => Ok(Rc::new(value)),
+            Err(message) => Err(interpreter.call_site_error(message))
+        }
+    }
+
+    fn arity(&self) -> usize {
+        self.arity
+    }
+}
@@ -1,5 +1,5 @@
 use std::rc::Rc;
-use crate::{callable::Callable, interpreter::{Interpreter, Value}, native::NativeFunction};
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::NativeFunction};
 
 pub struct Len {}
 
@@ -10,17 +10,15 @@ impl NativeFunction for Len {
 }
 
 impl Callable for Len {
-    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Rc<Value> {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
         let value = &arguments[0];
         let length = match &**value {
-            Value::String(string) => string.len(),
+            Value::String(string) => string.chars().count(),
             Value::Array(array) => array.borrow().len(),
-            _ => {
-                println!("Error: Only strings and arrays have length.");
-                return Rc::new(Value::Nil);
-            }
+            Value::Map(map) => map.borrow().len(),
+            _ => return Err(interpreter.call_site_error("Only strings, arrays and maps have length.".to_string()))
         };
-        Rc::new(Value::Number(length as f64))
+        Ok(Rc::new(Value::Number(length as f64)))
     }
 
     fn arity(&self) -> usize {
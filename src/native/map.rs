@@ -0,0 +1,99 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, map::MapKey, native::NativeFunction};
+
+const NOT_A_MAP: &str = "Expected a map as first argument.";
+const INVALID_KEY: &str = "Map keys must be strings, numbers, booleans or nil.";
+
+pub struct MapKeys {}
+
+impl NativeFunction for MapKeys {
+    fn get_name(&self) -> String {
+        "keys".to_string()
+    }
+}
+
+impl Callable for MapKeys {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        if let Value::Map(map) = &*arguments[0] {
+            let keys = map.borrow().keys();
+            return Ok(Rc::new(Value::Array(interpreter.heap.array(keys))));
+        }
+        Err(interpreter.call_site_error(NOT_A_MAP.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+pub struct MapValues {}
+
+impl NativeFunction for MapValues {
+    fn get_name(&self) -> String {
+        "values".to_string()
+    }
+}
+
+impl Callable for MapValues {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        if let Value::Map(map) = &*arguments[0] {
+            let values = map.borrow().values();
+            return Ok(Rc::new(Value::Array(interpreter.heap.array(values))));
+        }
+        Err(interpreter.call_site_error(NOT_A_MAP.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+pub struct MapHas {}
+
+impl NativeFunction for MapHas {
+    fn get_name(&self) -> String {
+        "has".to_string()
+    }
+}
+
+impl Callable for MapHas {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        if let Value::Map(map) = &*arguments[0] {
+            let Some(key) = MapKey::from_value(&arguments[1]) else {
+                return Err(interpreter.call_site_error(INVALID_KEY.to_string()));
+            };
+            return Ok(Rc::new(Value::Bool(map.borrow().has(&key))));
+        }
+        Err(interpreter.call_site_error(NOT_A_MAP.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// Removes the entry and returns its value, or `nil` when the key was not there.
+pub struct MapRemove {}
+
+impl NativeFunction for MapRemove {
+    fn get_name(&self) -> String {
+        "remove".to_string()
+    }
+}
+
+impl Callable for MapRemove {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        if let Value::Map(map) = &*arguments[0] {
+            let Some(key) = MapKey::from_value(&arguments[1]) else {
+                return Err(interpreter.call_site_error(INVALID_KEY.to_string()));
+            };
+            let value = map.borrow_mut().remove(&key);
+            return Ok(value.unwrap_or_else(|| Rc::new(Value::Nil)));
+        }
+        Err(interpreter.call_site_error(NOT_A_MAP.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
@@ -0,0 +1,160 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::{NativeFunction, integer_argument, number_argument}};
+
+/// A native taking one number, such as `floor` or `sin`.
+pub struct UnaryMath {
+    pub name: &'static str,
+    pub function: fn(f64) -> f64
+}
+
+impl NativeFunction for UnaryMath {
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+impl Callable for UnaryMath {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let x = number_argument(interpreter, &arguments, 0)?;
+        Ok(Rc::new(Value::Number((self.function)(x))))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// A native taking two numbers, such as `pow` or `atan2`.
+pub struct BinaryMath {
+    pub name: &'static str,
+    pub function: fn(f64, f64) -> f64
+}
+
+impl NativeFunction for BinaryMath {
+    fn get_name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+impl Callable for BinaryMath {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let x = number_argument(interpreter, &arguments, 0)?;
+        let y = number_argument(interpreter, &arguments, 1)?;
+        Ok(Rc::new(Value::Number((self.function)(x, y))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+pub struct Sqrt {}
+
+impl NativeFunction for Sqrt {
+    fn get_name(&self) -> String {
+        "sqrt".to_string()
+    }
+}
+
+impl Callable for Sqrt {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let x = number_argument(interpreter, &arguments, 0)?;
+        if x < 0.0 {
+            return Err(interpreter.call_site_error("Cannot take the square root of a negative number.".to_string()));
+        }
+        Ok(Rc::new(Value::Number(x.sqrt())))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// The natural logarithm.
+pub struct Log {}
+
+impl NativeFunction for Log {
+    fn get_name(&self) -> String {
+        "log".to_string()
+    }
+}
+
+impl Callable for Log {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let x = number_argument(interpreter, &arguments, 0)?;
+        if x <= 0.0 {
+            return Err(interpreter.call_site_error("Can only take the logarithm of a positive number.".to_string()));
+        }
+        Ok(Rc::new(Value::Number(x.ln())))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// Uniform in `[0, 1)`.
+pub struct RandomNumber {}
+
+impl NativeFunction for RandomNumber {
+    fn get_name(&self) -> String {
+        "random".to_string()
+    }
+}
+
+impl Callable for RandomNumber {
+    fn call(&self, interpreter: &mut Interpreter, _arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        Ok(Rc::new(Value::Number(interpreter.random.next_f64())))
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+}
+
+/// `random_int(lo, hi)`, uniform over the integers from `lo` to `hi` inclusive.
+pub struct RandomInt {}
+
+impl NativeFunction for RandomInt {
+    fn get_name(&self) -> String {
+        "random_int".to_string()
+    }
+}
+
+impl Callable for RandomInt {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let lo = integer_argument(interpreter, &arguments, 0)?;
+        let hi = integer_argument(interpreter, &arguments, 1)?;
+        if lo > hi {
+            return Err(interpreter.call_site_error("Lower bound must not be greater than upper bound.".to_string()));
+        }
+        let count = (hi as i128 - lo as i128 + 1) as f64;
+        let value = lo as i128 + (interpreter.random.next_f64() * count) as i128;
+        Ok(Rc::new(Value::Number(value.min(hi as i128) as f64)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// Restarts `random` and `random_int` from a seed, so that the numbers that follow are the same every run.
+pub struct Seed {}
+
+impl NativeFunction for Seed {
+    fn get_name(&self) -> String {
+        "seed".to_string()
+    }
+}
+
+impl Callable for Seed {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let seed = integer_argument(interpreter, &arguments, 0)?;
+        interpreter.random.seed(seed as u64);
+        Ok(Rc::new(Value::Nil))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
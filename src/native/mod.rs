@@ -1,14 +1,29 @@
+use crate::array::Array;
 use crate::callable::Callable;
 use crate::environment::Environment;
-use crate::interpreter::Value;
-use crate::native::array::{ArrayPop, ArrayPush};
+use crate::interpreter::{ErrType, Interpreter, Value};
+use crate::native::array::{
+    ArrayConcat, ArrayFilter, ArrayForEach, ArrayInsert, ArrayMap, ArrayPop, ArrayPush, ArrayReduce, ArrayRemoveAt,
+    ArrayReverse, ArraySlice, ArraySort, Range
+};
 use crate::native::clock::Clock;
+use crate::native::gc::{Gc, HeapSize};
 use crate::native::len::Len;
+use crate::native::math::{BinaryMath, Log, RandomInt, RandomNumber, Seed, Sqrt, UnaryMath};
+use crate::native::map::{MapHas, MapKeys, MapRemove, MapValues};
+use crate::native::search::{Contains, IndexOf};
+use crate::native::string::{Chr, EndsWith, Join, Lower, Ordinal, Repeat, Replace, Split, StartsWith, Substr, Trim, Upper};
 use std::cell::RefCell;
 use std::rc::Rc;
 mod clock;
+mod gc;
 mod array;
 mod len;
+mod map;
+mod math;
+mod search;
+mod string;
+pub mod host;
 
 pub trait NativeFunction: Callable {
     fn get_name(&self) -> String;
@@ -20,10 +35,105 @@ impl PartialEq for dyn NativeFunction {
     }
 }
 
+/// The most bytes or elements a native will build at once, well short of what would exhaust memory.
+const MAX_LENGTH: usize = 1 << 28;
+
+const ORDINALS: [&str; 3] = ["first", "second", "third"];
+
+fn number_argument(interpreter: &Interpreter, arguments: &[Rc<Value>], position: usize) -> Result<f64, ErrType> {
+    if let Value::Number(number) = &*arguments[position] {
+        return Ok(*number);
+    }
+    Err(interpreter.call_site_error(format!("Expected a number as {} argument.", ORDINALS[position])))
+}
+
+/// Above 2^53 a number no longer tells neighbouring integers apart.
+const MAX_INTEGER: f64 = 9007199254740992.0;
+
+fn integer_argument(interpreter: &Interpreter, arguments: &[Rc<Value>], position: usize) -> Result<i64, ErrType> {
+    if let Value::Number(number) = &*arguments[position] && number.fract() == 0.0 {
+        if number.abs() > MAX_INTEGER {
+            return Err(interpreter.call_site_error(format!("Expected an integer between -2^53 and 2^53 as {} argument.", ORDINALS[position])));
+        }
+        return Ok(*number as i64);
+    }
+    Err(interpreter.call_site_error(format!("Expected an integer as {} argument.", ORDINALS[position])))
+}
+
+fn string_argument(interpreter: &Interpreter, arguments: &[Rc<Value>], position: usize) -> Result<String, ErrType> {
+    if let Value::String(string) = &*arguments[position] {
+        return Ok(string.clone());
+    }
+    Err(interpreter.call_site_error(format!("Expected a string as {} argument.", ORDINALS[position])))
+}
+
+fn array_argument(interpreter: &Interpreter, arguments: &[Rc<Value>], position: usize) -> Result<Rc<RefCell<Array>>, ErrType> {
+    if let Value::Array(array) = &*arguments[position] {
+        return Ok(array.clone());
+    }
+    Err(interpreter.call_site_error(format!("Expected an array as {} argument.", ORDINALS[position])))
+}
+
+fn function_argument(interpreter: &Interpreter, arguments: &[Rc<Value>], position: usize) -> Result<Rc<Value>, ErrType> {
+    if let Value::Function(_) | Value::NativeFunction(_) | Value::Class(_) = &*arguments[position] {
+        return Ok(arguments[position].clone());
+    }
+    Err(interpreter.call_site_error(format!("Expected a function as {} argument.", ORDINALS[position])))
+}
+
 pub fn init_native_functions(environment: Rc<RefCell<Environment>>) {
     let mut environment = environment.borrow_mut();
     environment.define("clock".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Clock{})))));
     environment.define("push_array".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayPush{})))));
     environment.define("pop_array".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayPop{})))));
+    environment.define("map".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayMap{})))));
+    environment.define("filter".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayFilter{})))));
+    environment.define("reduce".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayReduce{})))));
+    environment.define("for_each".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayForEach{})))));
+    environment.define("sort".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArraySort{})))));
+    environment.define("reverse".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayReverse{})))));
+    environment.define("slice".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArraySlice{})))));
+    environment.define("concat".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayConcat{})))));
+    environment.define("insert".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayInsert{})))));
+    environment.define("remove_at".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(ArrayRemoveAt{})))));
+    environment.define("range".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Range{})))));
+    environment.define("contains".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Contains{})))));
     environment.define("len".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Len{})))));
+    environment.define("keys".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(MapKeys{})))));
+    environment.define("values".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(MapValues{})))));
+    environment.define("has".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(MapHas{})))));
+    environment.define("remove".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(MapRemove{})))));
+    environment.define("substr".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Substr{})))));
+    environment.define("index_of".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(IndexOf{})))));
+    environment.define("split".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Split{})))));
+    environment.define("join".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Join{})))));
+    environment.define("trim".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Trim{})))));
+    environment.define("upper".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Upper{})))));
+    environment.define("lower".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Lower{})))));
+    environment.define("replace".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Replace{})))));
+    environment.define("starts_with".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(StartsWith{})))));
+    environment.define("ends_with".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(EndsWith{})))));
+    environment.define("repeat".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Repeat{})))));
+    environment.define("chr".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Chr{})))));
+    environment.define("ord".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Ordinal{})))));
+    for (name, function) in [
+        ("abs", f64::abs as fn(f64) -> f64), ("floor", f64::floor), ("ceil", f64::ceil), ("round", f64::round),
+        ("sin", f64::sin), ("cos", f64::cos), ("tan", f64::tan), ("exp", f64::exp)
+    ] {
+        environment.define(name.to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(UnaryMath{name, function})))));
+    }
+    for (name, function) in [
+        ("pow", f64::powf as fn(f64, f64) -> f64), ("min", f64::min), ("max", f64::max), ("atan2", f64::atan2)
+    ] {
+        environment.define(name.to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(BinaryMath{name, function})))));
+    }
+    environment.define("sqrt".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Sqrt{})))));
+    environment.define("log".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Log{})))));
+    environment.define("random".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(RandomNumber{})))));
+    environment.define("random_int".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(RandomInt{})))));
+    environment.define("seed".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Seed{})))));
+    environment.define("PI".to_string(), Rc::new(Value::Number(std::f64::consts::PI)));
+    environment.define("E".to_string(), Rc::new(Value::Number(std::f64::consts::E)));
+    environment.define("gc".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(Gc{})))));
+    environment.define("heap_size".to_string(), Rc::new(Value::NativeFunction(Rc::new(RefCell::new(HeapSize{})))));
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::NativeFunction};
+
+const NOT_SEARCHABLE: &str = "Expected a string or array as first argument.";
+
+/// Where `needle` first occurs: a substring of a string, counted in characters, or an element
+/// of an array. `None` when it does not occur.
+fn find(interpreter: &Interpreter, haystack: &Value, needle: &Value) -> Result<Option<usize>, ErrType> {
+    match haystack {
+        Value::String(string) => {
+            let Value::String(needle) = needle else {
+                return Err(interpreter.call_site_error("Expected a string as second argument.".to_string()));
+            };
+            Ok(string.find(needle.as_str()).map(|byte| string[..byte].chars().count()))
+        }
+        Value::Array(array) => Ok(array.borrow().elements.iter().position(|element| **element == *needle)),
+        _ => Err(interpreter.call_site_error(NOT_SEARCHABLE.to_string()))
+    }
+}
+
+pub struct IndexOf {}
+
+impl NativeFunction for IndexOf {
+    fn get_name(&self) -> String {
+        "index_of".to_string()
+    }
+}
+
+impl Callable for IndexOf {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let index = find(interpreter, &arguments[0], &arguments[1])?;
+        Ok(Rc::new(Value::Number(index.map_or(-1.0, |index| index as f64))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+pub struct Contains {}
+
+impl NativeFunction for Contains {
+    fn get_name(&self) -> String {
+        "contains".to_string()
+    }
+}
+
+impl Callable for Contains {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let index = find(interpreter, &arguments[0], &arguments[1])?;
+        Ok(Rc::new(Value::Bool(index.is_some())))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
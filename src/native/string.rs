@@ -0,0 +1,277 @@
+use std::rc::Rc;
+use crate::{callable::Callable, interpreter::{ErrType, Interpreter, Value}, native::{MAX_LENGTH, NativeFunction, array_argument, integer_argument, string_argument}};
+
+// positions and lengths count Unicode scalar values, like `len` and `s[i]`
+
+fn string_value(string: String) -> Rc<Value> {
+    Rc::new(Value::String(string))
+}
+
+/// `substr(s, start, length)`, which must lie within the string.
+pub struct Substr {}
+
+impl NativeFunction for Substr {
+    fn get_name(&self) -> String {
+        "substr".to_string()
+    }
+}
+
+impl Callable for Substr {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let start = integer_argument(interpreter, &arguments, 1)?;
+        let length = integer_argument(interpreter, &arguments, 2)?;
+        let chars = string.chars().count() as i64;
+        if start < 0 || length < 0 || start > chars || length > chars - start {
+            return Err(interpreter.call_site_error("Substring out of range.".to_string()));
+        }
+        Ok(string_value(string.chars().skip(start as usize).take(length as usize).collect()))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+}
+
+/// Splits on every occurrence of the separator, or into characters when it is empty.
+pub struct Split {}
+
+impl NativeFunction for Split {
+    fn get_name(&self) -> String {
+        "split".to_string()
+    }
+}
+
+impl Callable for Split {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let separator = string_argument(interpreter, &arguments, 1)?;
+        let parts: Vec<Rc<Value>> = if separator.is_empty() {
+            string.chars().map(|c| string_value(c.to_string())).collect()
+        } else {
+            string.split(&separator).map(|part| string_value(part.to_string())).collect()
+        };
+        Ok(Rc::new(Value::Array(interpreter.heap.array(parts))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// Joins the elements of an array, shown the way `print` shows them, with a separator.
+pub struct Join {}
+
+impl NativeFunction for Join {
+    fn get_name(&self) -> String {
+        "join".to_string()
+    }
+}
+
+impl Callable for Join {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let array = array_argument(interpreter, &arguments, 0)?;
+        let separator = string_argument(interpreter, &arguments, 1)?;
+        let parts: Vec<String> = array.borrow().elements.iter().map(|element| element.to_string()).collect();
+        Ok(string_value(parts.join(&separator)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+pub struct Trim {}
+
+impl NativeFunction for Trim {
+    fn get_name(&self) -> String {
+        "trim".to_string()
+    }
+}
+
+impl Callable for Trim {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        Ok(string_value(string.trim().to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+pub struct Upper {}
+
+impl NativeFunction for Upper {
+    fn get_name(&self) -> String {
+        "upper".to_string()
+    }
+}
+
+impl Callable for Upper {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        Ok(string_value(string.to_uppercase()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+pub struct Lower {}
+
+impl NativeFunction for Lower {
+    fn get_name(&self) -> String {
+        "lower".to_string()
+    }
+}
+
+impl Callable for Lower {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        Ok(string_value(string.to_lowercase()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// Replaces every occurrence of the second argument with the third.
+pub struct Replace {}
+
+impl NativeFunction for Replace {
+    fn get_name(&self) -> String {
+        "replace".to_string()
+    }
+}
+
+impl Callable for Replace {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let from = string_argument(interpreter, &arguments, 1)?;
+        let to = string_argument(interpreter, &arguments, 2)?;
+        if from.is_empty() {
+            return Err(interpreter.call_site_error("Cannot replace an empty string.".to_string()));
+        }
+        Ok(string_value(string.replace(&from, &to)))
+    }
+
+    fn arity(&self) -> usize {
+        3
+    }
+}
+
+pub struct StartsWith {}
+
+impl NativeFunction for StartsWith {
+    fn get_name(&self) -> String {
+        "starts_with".to_string()
+    }
+}
+
+impl Callable for StartsWith {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let prefix = string_argument(interpreter, &arguments, 1)?;
+        Ok(Rc::new(Value::Bool(string.starts_with(&prefix))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+pub struct EndsWith {}
+
+impl NativeFunction for EndsWith {
+    fn get_name(&self) -> String {
+        "ends_with".to_string()
+    }
+}
+
+impl Callable for EndsWith {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let suffix = string_argument(interpreter, &arguments, 1)?;
+        Ok(Rc::new(Value::Bool(string.ends_with(&suffix))))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+pub struct Repeat {}
+
+impl NativeFunction for Repeat {
+    fn get_name(&self) -> String {
+        "repeat".to_string()
+    }
+}
+
+impl Callable for Repeat {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let count = integer_argument(interpreter, &arguments, 1)?;
+        if count < 0 {
+            return Err(interpreter.call_site_error("Cannot repeat a string a negative number of times.".to_string()));
+        }
+        if string.len().checked_mul(count as usize).is_none_or(|length| length > MAX_LENGTH) {
+            return Err(interpreter.call_site_error("Repeated string is too long.".to_string()));
+        }
+        Ok(string_value(string.repeat(count as usize)))
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+}
+
+/// The one-character string for a Unicode code point.
+pub struct Chr {}
+
+impl NativeFunction for Chr {
+    fn get_name(&self) -> String {
+        "chr".to_string()
+    }
+}
+
+impl Callable for Chr {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let code = integer_argument(interpreter, &arguments, 0)?;
+        let Some(c) = u32::try_from(code).ok().and_then(char::from_u32) else {
+            return Err(interpreter.call_site_error(format!("{code} is not a valid code point.")));
+        };
+        Ok(string_value(c.to_string()))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+/// The code point of a one-character string.
+pub struct Ordinal {}
+
+impl NativeFunction for Ordinal {
+    fn get_name(&self) -> String {
+        "ord".to_string()
+    }
+}
+
+impl Callable for Ordinal {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Rc<Value>>) -> Result<Rc<Value>, ErrType> {
+        let string = string_argument(interpreter, &arguments, 0)?;
+        let mut chars = string.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(interpreter.call_site_error("Expected a single character.".to_string()));
+        };
+        Ok(Rc::new(Value::Number(c as u32 as f64)))
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+}
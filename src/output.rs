@@ -0,0 +1,46 @@
+use std::{cell::RefCell, io::{self, Write}, rc::Rc};
+
+/// Where the interpreter writes. Clones share the same destination, so a `Buffer`
+/// handed to the interpreter can still be read by whoever created it.
+#[derive(Clone)]
+pub enum Output {
+    Stdout,
+    Stderr,
+    /// Keeps everything in memory, e.g. to compare a program's output in tests.
+    Buffer(Rc<RefCell<Vec<u8>>>),
+    Writer(Rc<RefCell<dyn Write>>)
+}
+
+impl Output {
+    pub fn buffer() -> Self {
+        Self::Buffer(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    /// Everything written so far, for a `Buffer`; empty for the other destinations.
+    pub fn contents(&self) -> String {
+        match self {
+            Self::Buffer(buffer) => String::from_utf8_lossy(&buffer.borrow()).to_string(),
+            _ => String::new()
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout => io::stdout().write(buf),
+            Self::Stderr => io::stderr().write(buf),
+            Self::Buffer(buffer) => buffer.borrow_mut().write(buf),
+            Self::Writer(writer) => writer.borrow_mut().write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout => io::stdout().flush(),
+            Self::Stderr => io::stderr().flush(),
+            Self::Buffer(_) => Ok(()),
+            Self::Writer(writer) => writer.borrow_mut().flush()
+        }
+    }
+}
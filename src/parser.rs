@@ -1,48 +1,59 @@
 use std::cell::RefCell;
+use crate::diagnostic::{Diagnostic, Span, Stage};
 use crate::error::ErrorReporter;
 use crate::token::{Literal, Token, TokenType};
 use crate::ast::expr::*;
 use crate::ast::stmt::*;
 
 pub struct Parser {
-    file_path: String,
     tokens: Vec<Token>,
     current: RefCell<usize>,
-    pub had_error: RefCell<bool>,
-    errors: RefCell<Vec<(usize, usize, String)>>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
     loop_depth: RefCell<usize>
 }
 
 impl Parser {
-    pub fn new(file_path: String, tokens: Vec<Token>) -> Self {
+    pub fn new(tokens: Vec<Token>) -> Self {
         Self { 
-            file_path: file_path,
             tokens: tokens, 
             current: RefCell::new(0),
-            had_error: RefCell::new(false),
-            errors: RefCell::new(Vec::new()),
+            diagnostics: RefCell::new(Vec::new()),
             loop_depth: RefCell::new(0)
         }
     }
 
-    pub fn parse(&self) -> Vec<Stmt> {
+    /// Parses the whole token stream. Declarations that fail to parse are kept as `Stmt::Error`
+    /// placeholders, so the returned tree is always complete.
+    pub fn parse(&self) -> ParseResult {
         let mut stmts = Vec::new();
         while !self.is_end() {
-            if let Ok(stmt) =  self.parse_decl() && let Some(stmt) = stmt {
-                stmts.push(stmt);
-            }
+            stmts.push(self.parse_decl());
+        }
+        ParseResult {
+            stmts: stmts,
+            diagnostics: self.diagnostics.take()
         }
-        stmts
+    }
+}
+
+pub struct ParseResult {
+    pub stmts: Vec<Stmt>,
+    pub diagnostics: Vec<Diagnostic>
+}
+
+impl ParseResult {
+    pub fn has_errors(&self) -> bool {
+        !self.diagnostics.is_empty()
     }
 }
 
 // expr
 impl Parser {
-    fn parse_expr(&self) -> Result<Expr, (Token, String)> {
+    fn parse_expr(&self) -> Result<Expr, (Box<Token>, String)> {
         self.parse_comma()
     }
 
-    fn parse_comma(&self) -> Result<Expr, (Token, String)> {
+    fn parse_comma(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_assignment()?;
         while self.is_match(vec![TokenType::Comma]) {
             let operator = self.previous();
@@ -58,45 +69,73 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_assignment(&self) -> Result<Expr, (Token, String)> {
+    fn parse_assignment(&self) -> Result<Expr, (Box<Token>, String)> {
         let expr = self.parse_ternary()?;
         if self.is_match(vec![TokenType::Equal]) {
             let equals = self.previous();
             let value = self.parse_assignment()?;
-            if let Expr::Identifier(identifier) = expr {
-                let name = identifier.name;
-                return Ok(Expr::Assign(
-                    AssignExpr { 
-                        name: name, 
-                        value: Box::new(value) 
-                    }
-                ));
-            }
-            else if let Expr::Get(expr_get) = expr {
-                return Ok(Expr::Set(
-                    SetExpr {
-                        object: expr_get.object,
-                        name: expr_get.name,
-                        value: Box::new(value)
-                    }
-                ));
-            }
-            else if let Expr::SubscriptGet(subscript_get_expr) = expr {
-                return Ok(Expr::SubscriptSet(
-                    SubscriptSetExpr {
-                        array: subscript_get_expr.array,
-                        index: subscript_get_expr.index,
-                        value: Box::new(value),
-                        bracket: subscript_get_expr.bracket
-                    }
-                ))
-            }
-            return Err(self.handle_error(equals, "Invalid assignment target.".to_string()));
+            return self.assignment_target(expr, value, equals, None, false);
+        }
+        if self.is_match(vec![
+            TokenType::PlusEqual, TokenType::MinusEqual, TokenType::StarEqual, TokenType::SlashEqual, TokenType::PercentEqual
+        ]) {
+            let operator = self.previous();
+            let value = self.parse_assignment()?;
+            return self.assignment_target(expr, value, operator, Some(operator.clone()), false);
         }
         Ok(expr)
     }
 
-    fn parse_ternary(&self) -> Result<Expr, (Token, String)> {
+    /// Turns the expression on the left of an assignment into the assignment itself.
+    fn assignment_target(&self, expr: Expr, value: Expr, equals: &Token, op: Option<Token>, postfix: bool) -> Result<Expr, (Box<Token>, String)> {
+        if let Expr::Identifier(identifier) = expr {
+            let name = identifier.name;
+            return Ok(Expr::Assign(
+                AssignExpr { 
+                    name,
+                    value: Box::new(value),
+                    op,
+                    postfix
+                }
+            ));
+        }
+        else if let Expr::Get(expr_get) = expr {
+            return Ok(Expr::Set(
+                SetExpr {
+                    object: expr_get.object,
+                    name: expr_get.name,
+                    value: Box::new(value),
+                    op,
+                    postfix
+                }
+            ));
+        }
+        else if let Expr::SubscriptGet(subscript_get_expr) = expr {
+            return Ok(Expr::SubscriptSet(
+                SubscriptSetExpr {
+                    array: subscript_get_expr.array,
+                    index: subscript_get_expr.index,
+                    value: Box::new(value),
+                    bracket: subscript_get_expr.bracket,
+                    op,
+                    postfix
+                }
+            ))
+        }
+        Err(self.handle_error(equals, "Invalid assignment target.".to_string()))
+    }
+
+    /// `++target` or `target++`, which add one to the target like `target += 1`.
+    fn increment(&self, target: Expr, operator: &Token, postfix: bool) -> Result<Expr, (Box<Token>, String)> {
+        let one = Expr::Literal(
+            LiteralExpr {
+                content: Literal::Number("1".to_string())
+            }
+        );
+        self.assignment_target(target, one, operator, Some(operator.clone()), postfix)
+    }
+
+    fn parse_ternary(&self) -> Result<Expr, (Box<Token>, String)> {
         let condition = self.parse_logic_or()?;
         if self.is_match(vec![TokenType::Question]) {
             let then_expr = self.parse_expr()?;
@@ -113,7 +152,7 @@ impl Parser {
         Ok(condition)
     }
 
-    fn parse_logic_or(&self) -> Result<Expr, (Token, String)> {
+    fn parse_logic_or(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_logic_and()?;
         while self.is_match(vec![TokenType::Or]) {
             let operator = self.previous();
@@ -129,7 +168,7 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_logic_and(&self) -> Result<Expr, (Token, String)> {
+    fn parse_logic_and(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_equality()?;
         while self.is_match(vec![TokenType::And]) {
             let operator = self.previous();
@@ -145,7 +184,7 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_equality(&self) -> Result<Expr, (Token, String)> {
+    fn parse_equality(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_comparison()?;
         while self.is_match(vec![TokenType::BangEqual, TokenType::EqualEqual]) {
             let operator = self.previous();
@@ -161,11 +200,11 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_comparison(&self) -> Result<Expr, (Token, String)> {
-        let mut expr = self.parse_term()?;
+    fn parse_comparison(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut expr = self.parse_bitwise_or()?;
         while self.is_match(vec![TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual]) {
             let operator = self.previous();
-            let right = self.parse_term()?;
+            let right = self.parse_bitwise_or()?;
             expr = Expr::Binary(
                 BinaryExpr { 
                     op: operator.clone(),
@@ -177,7 +216,71 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_term(&self) -> Result<Expr, (Token, String)> {
+    fn parse_bitwise_or(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut expr = self.parse_bitwise_xor()?;
+        while self.is_match(vec![TokenType::Pipe]) {
+            let operator = self.previous();
+            let right = self.parse_bitwise_xor()?;
+            expr = Expr::Binary(
+                BinaryExpr {
+                    op: operator.clone(),
+                    lhs: Box::new(expr),
+                    rhs: Box::new(right)
+                }
+            );
+        }
+        Ok(expr)
+    }
+
+    fn parse_bitwise_xor(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut expr = self.parse_bitwise_and()?;
+        while self.is_match(vec![TokenType::Caret]) {
+            let operator = self.previous();
+            let right = self.parse_bitwise_and()?;
+            expr = Expr::Binary(
+                BinaryExpr {
+                    op: operator.clone(),
+                    lhs: Box::new(expr),
+                    rhs: Box::new(right)
+                }
+            );
+        }
+        Ok(expr)
+    }
+
+    fn parse_bitwise_and(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut expr = self.parse_shift()?;
+        while self.is_match(vec![TokenType::Ampersand]) {
+            let operator = self.previous();
+            let right = self.parse_shift()?;
+            expr = Expr::Binary(
+                BinaryExpr {
+                    op: operator.clone(),
+                    lhs: Box::new(expr),
+                    rhs: Box::new(right)
+                }
+            );
+        }
+        Ok(expr)
+    }
+
+    fn parse_shift(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut expr = self.parse_term()?;
+        while self.is_match(vec![TokenType::LessLess, TokenType::GreaterGreater]) {
+            let operator = self.previous();
+            let right = self.parse_term()?;
+            expr = Expr::Binary(
+                BinaryExpr {
+                    op: operator.clone(),
+                    lhs: Box::new(expr),
+                    rhs: Box::new(right)
+                }
+            );
+        }
+        Ok(expr)
+    }
+
+    fn parse_term(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_factor()?;
         while self.is_match(vec![TokenType::Minus, TokenType::Plus]) {
             let operator = self.previous();
@@ -193,9 +296,9 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_factor(&self) -> Result<Expr, (Token, String)> {
+    fn parse_factor(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_unary()?;
-        while self.is_match(vec![TokenType::Slash, TokenType::Star]) {
+        while self.is_match(vec![TokenType::Slash, TokenType::Star, TokenType::Percent, TokenType::TildeSlash]) {
             let operator = self.previous();
             let right = self.parse_unary()?;
             expr = Expr::Binary(
@@ -209,8 +312,8 @@ impl Parser {
         Ok(expr)
     }
 
-    fn parse_unary(&self) -> Result<Expr, (Token, String)> {
-        if self.is_match(vec![TokenType::Bang, TokenType::Minus]) {
+    fn parse_unary(&self) -> Result<Expr, (Box<Token>, String)> {
+        if self.is_match(vec![TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator = self.previous();
             let right = self.parse_unary()?;
             return Ok(Expr::Unary(
@@ -220,10 +323,37 @@ impl Parser {
                 }
             ));
         }
+        self.parse_power()
+    }
+
+    // binds tighter than unary operators on its left, so `-2 ** 2` is `-(2 ** 2)`
+    fn parse_power(&self) -> Result<Expr, (Box<Token>, String)> {
+        let expr = self.parse_prefix_increment()?;
+        if self.is_match(vec![TokenType::StarStar]) {
+            let operator = self.previous();
+            // right-associative
+            let right = self.parse_unary()?;
+            return Ok(Expr::Binary(
+                BinaryExpr {
+                    op: operator.clone(),
+                    lhs: Box::new(expr),
+                    rhs: Box::new(right)
+                }
+            ));
+        }
+        Ok(expr)
+    }
+
+    fn parse_prefix_increment(&self) -> Result<Expr, (Box<Token>, String)> {
+        if self.is_match(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.previous();
+            let target = self.parse_call()?;
+            return self.increment(target, operator, false);
+        }
         self.parse_call()
     }
 
-    fn parse_call(&self) -> Result<Expr, (Token, String)> {
+    fn parse_call(&self) -> Result<Expr, (Box<Token>, String)> {
         let mut expr = self.parse_primary()?;
         loop {
             if self.is_match(vec![TokenType::LeftParen]) {
@@ -250,10 +380,13 @@ impl Parser {
                 break;
             }
         }
+        if self.is_match(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            return self.increment(expr, self.previous(), true);
+        }
         Ok(expr)
     }
 
-    fn parse_primary(&self) -> Result<Expr, (Token, String)> {
+    fn parse_primary(&self) -> Result<Expr, (Box<Token>, String)> {
         if self.is_match(vec![TokenType::False]) {
             return Ok(Expr::Literal(
                 LiteralExpr { 
@@ -282,6 +415,9 @@ impl Parser {
                 }
             ));
         }
+        if self.check(TokenType::Interpolation) {
+            return self.parse_interpolation();
+        }
         if self.is_match(vec![TokenType::Super]) {
             let keyword = self.previous();
             self.consume(TokenType::Dot, "Expect '.' after 'super'.".to_string())?;
@@ -300,6 +436,24 @@ impl Parser {
                 Identifier { name: self.previous().clone() }
             ))
         }
+        if self.check(TokenType::Fun) && self.check_next(TokenType::LeftParen) {
+            let keyword = self.next_token();
+            self.next_token();
+            let decl = self.parse_function(anonymous(keyword), "function")?;
+            return Ok(Expr::Lambda(LambdaExpr { decl }));
+        }
+        if self.check(TokenType::LeftParen) && self.is_arrow_function() {
+            self.next_token();
+            let params = self.parse_params()?;
+            let arrow = self.consume(TokenType::Arrow, "Expect '=>' after parameters.".to_string())?;
+            let body = self.parse_assignment()?;
+            let decl = FunDecl {
+                name: anonymous(arrow),
+                params,
+                body: vec![Stmt::Return(ReturnStmt { keyword: arrow.clone(), value: Some(body) })]
+            };
+            return Ok(Expr::Lambda(LambdaExpr { decl }));
+        }
         if self.is_match(vec![TokenType::LeftParen]) {
             let expr = self.parse_expr()?;
             self.consume(TokenType::RightParen, "Expect ')' afer expression.".to_string())?;
@@ -320,13 +474,58 @@ impl Parser {
             self.consume(TokenType::RightSquareBracket, "Expect ']' after array elements.".to_string())?;
             return Ok(Expr::Array(ArrayExpr { elements: elements }));
         }
+        if self.is_match(vec![TokenType::LeftBrace]) {
+            let brace = self.previous().clone();
+            let mut entries = Vec::new();
+            if !self.check(TokenType::RightBrace) {
+                loop {
+                    let key = self.parse_assignment()?;
+                    self.consume(TokenType::Colon, "Expect ':' after map key.".to_string())?;
+                    entries.push((key, self.parse_assignment()?));
+                    if !self.is_match(vec![TokenType::Comma]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after map entries.".to_string())?;
+            return Ok(Expr::Map(MapExpr { brace, entries }));
+        }
         Err(self.handle_error(self.peek(), "Expect expression.".to_string()))
     } 
+
+    /// The scanner splits `"a${b}c"` into an `Interpolation` token for `a`, the tokens of `b` and a
+    /// `String` token for `c`, with one `Interpolation` token per `${`.
+    fn parse_interpolation(&self) -> Result<Expr, (Box<Token>, String)> {
+        let mut parts = Vec::new();
+        while self.is_match(vec![TokenType::Interpolation]) {
+            parts.push(Expr::Literal(
+                LiteralExpr {
+                    content: self.previous().literal.clone().unwrap()
+                }
+            ));
+            // the part after `}` comes straight after `${`
+            if self.peek().text.starts_with('}') {
+                return Err(self.handle_error(self.peek(), "Expect expression.".to_string()));
+            }
+            parts.push(self.parse_expr()?);
+            if !self.check(TokenType::Interpolation) {
+                let rest = self.consume(TokenType::String, "Expect '}' after interpolated expression.".to_string())?;
+                parts.push(Expr::Literal(
+                    LiteralExpr {
+                        content: rest.literal.clone().unwrap()
+                    }
+                ));
+                // an `Interpolation` token after this one continues an enclosing string
+                break;
+            }
+        }
+        Ok(Expr::Interpolation(InterpolationExpr { parts }))
+    }
 }
 
 // stmt
 impl Parser {
-    fn parse_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         if self.is_match(vec![TokenType::Print]) {
             return self.parse_print_stmt();
         }
@@ -351,10 +550,16 @@ impl Parser {
         if self.is_match(vec![TokenType::Return]) {
             return self.parse_return_stmt();
         }
+        if self.is_match(vec![TokenType::Throw]) {
+            return self.parse_throw_stmt();
+        }
+        if self.is_match(vec![TokenType::Try]) {
+            return self.parse_try_stmt();
+        }
         self.parse_expr_stmt()
     }
 
-    fn parse_print_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_print_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         let expr = self.parse_expr()?;
         self.consume(TokenType::Semicolon, "Expect ';' after expression.".to_string())?;
         Ok(Stmt::Print(
@@ -364,7 +569,7 @@ impl Parser {
         ))
     }
 
-    fn parse_expr_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_expr_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         let expr = self.parse_expr()?;
         self.consume(TokenType::Semicolon, "Expect ';' after expression.".to_string())?;
         Ok(Stmt::Expr(
@@ -374,7 +579,7 @@ impl Parser {
         ))
     }
 
-    fn parse_if_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_if_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         self.consume(TokenType::LeftParen, "Expect '(' after 'if'.".to_string())?;
         let condition = self.parse_expr()?;
         self.consume(TokenType::RightParen, "Expect ')' after if condition.".to_string())?;
@@ -392,7 +597,7 @@ impl Parser {
         ))
     }
 
-    fn parse_while_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_while_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         self.consume(TokenType::LeftParen, "Expect '(' after 'while'.".to_string())?;
         let condition = self.parse_expr()?;
         self.consume(TokenType::RightParen, "Expect ')' after condition.".to_string())?;
@@ -411,8 +616,13 @@ impl Parser {
         result
     }
 
-    fn parse_for_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_for_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.".to_string())?;
+        // `in` is only a keyword here, so it stays usable as a variable name
+        if self.check(TokenType::Var) && self.check_next(TokenType::Identifier)
+            && self.tokens.get(*self.current.borrow() + 2).is_some_and(|token| token.token_type == TokenType::Identifier && token.text == "in") {
+            return self.parse_for_in_stmt();
+        }
         let mut init = None;
         if self.is_match(vec![TokenType::Var]) {
             init = Some(self.parse_var_decl()?);
@@ -479,23 +689,42 @@ impl Parser {
         Ok(stmt)
     }
 
-    fn parse_break_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_for_in_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
+        self.next_token();
+        let name = self.next_token().clone();
+        let keyword = self.next_token().clone();
+        let iterable = self.parse_expr()?;
+        self.consume(TokenType::RightParen, "Expect ')' after for clauses.".to_string())?;
+        *self.loop_depth.borrow_mut() += 1;
+        let body = self.parse_stmt();
+        *self.loop_depth.borrow_mut() -= 1;
+        Ok(Stmt::ForIn(
+            ForInStmt {
+                name,
+                keyword,
+                iterable,
+                body: Box::new(body?)
+            }
+        ))
+    }
+
+    fn parse_break_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         if *self.loop_depth.borrow() == 0 {
-            return Err(self.handle_error(self.previous(), "Must be inside a loop to use 'break'.".to_string()));
+            return Err(self.handle_error(self.previous(), "Can't use 'break' outside of a loop.".to_string()));
         }
         self.consume(TokenType::Semicolon, "Expect ';' after 'break'.".to_string())?;
         Ok(Stmt::Break)
     }
 
-    fn parse_continue_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_continue_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         if *self.loop_depth.borrow() == 0 {
-            return Err(self.handle_error(self.previous(), "Must be inside a loop to use 'continue'.".to_string()));
+            return Err(self.handle_error(self.previous(), "Can't use 'continue' outside of a loop.".to_string()));
         }
         self.consume(TokenType::Semicolon, "Expect ';' after 'continue'.".to_string())?;
         Ok(Stmt::Continue)
     }
 
-    fn parse_return_stmt(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_return_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
         let keyword = self.previous();
         let mut value = None;
         if !self.check(TokenType::Semicolon) {
@@ -510,12 +739,57 @@ impl Parser {
         ))
     }
 
-    fn parse_block(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_throw_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
+        let keyword = self.previous().clone();
+        let value = self.parse_expr()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after thrown value.".to_string())?;
+        Ok(Stmt::Throw(
+            ThrowStmt {
+                keyword,
+                value
+            }
+        ))
+    }
+
+    fn parse_try_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
+        let keyword = self.previous().clone();
+        let body = self.parse_clause_body("try")?;
+        let mut catch_clause = None;
+        if self.is_match(vec![TokenType::Catch]) {
+            self.consume(TokenType::LeftParen, "Expect '(' after 'catch'.".to_string())?;
+            let name = self.consume(TokenType::Identifier, "Expect error variable name.".to_string())?.clone();
+            self.consume(TokenType::RightParen, "Expect ')' after error variable name.".to_string())?;
+            catch_clause = Some(CatchClause { name, body: self.parse_clause_body("catch")? });
+        }
+        let mut finally_body = None;
+        if self.is_match(vec![TokenType::Finally]) {
+            finally_body = Some(self.parse_clause_body("finally")?);
+        }
+        if catch_clause.is_none() && finally_body.is_none() {
+            return Err(self.handle_error(self.peek(), "Expect 'catch' or 'finally' after try block.".to_string()));
+        }
+        Ok(Stmt::Try(
+            TryStmt {
+                keyword,
+                body,
+                catch_clause,
+                finally_body
+            }
+        ))
+    }
+
+    fn parse_clause_body(&self, clause: &str) -> Result<Vec<Stmt>, (Box<Token>, String)> {
+        self.consume(TokenType::LeftBrace, format!("Expect '{{' after '{clause}'."))?;
+        match self.parse_block()? {
+            Stmt::Block(block) => Ok(block.stmts),
+            _ => Ok(Vec::new())
+        }
+    }
+
+    fn parse_block(&self) -> Result<Stmt, (Box<Token>, String)> {
         let mut stmts = Vec::new();
         while !self.check(TokenType::RightBrace) && !self.is_end() {
-            if let Some(stmt) = self.parse_decl()? {
-                stmts.push(stmt);
-            }
+            stmts.push(self.parse_decl());
         }
         self.consume(TokenType::RightBrace, "Expect '}' after block.".to_string())?;
         Ok(Stmt::Block(
@@ -526,30 +800,86 @@ impl Parser {
 
 // decl
 impl Parser {
-    fn parse_decl(&self) -> Result<Option<Stmt>, (Token, String)> {
-        if self.is_match(vec![TokenType::Var]) {
-            if let Ok(var_decl) = self.parse_var_decl() {
-                return Ok(Some(var_decl));
-            }
+    fn parse_decl(&self) -> Stmt {
+        let result = if self.is_match(vec![TokenType::Var]) {
+            self.parse_var_decl()
         }
-        else if self.is_match(vec![TokenType::Fun]) {
-            if let Ok(fun_decl) = self.parse_fun_decl("function".to_string()) {
-                return Ok(Some(fun_decl));
-            }
+        // `fun (` starts an anonymous function, which is an expression statement
+        else if self.check(TokenType::Fun) && !self.check_next(TokenType::LeftParen) {
+            self.next_token();
+            self.parse_fun_decl("function".to_string())
         }
         else if self.is_match(vec![TokenType::Class]) {
-            if let Ok(class_decl) = self.parse_class_decl() {
-                return Ok(Some(class_decl));
+            self.parse_class_decl()
+        }
+        else if self.is_match(vec![TokenType::Import]) {
+            self.parse_import_stmt()
+        }
+        else if self.is_match(vec![TokenType::Export]) {
+            self.parse_export_decl()
+        }
+        else {
+            self.parse_stmt()
+        };
+        match result {
+            Ok(stmt) => stmt,
+            Err((token, _)) => {
+                self.synchronize();
+                Stmt::Error(ErrorStmt { token: *token })
             }
         }
-        else if let Ok(stmt) = self.parse_stmt() {
-            return Ok(Some(stmt));
+    }
+
+    fn parse_import_stmt(&self) -> Result<Stmt, (Box<Token>, String)> {
+        let keyword = self.previous().clone();
+        let mut names = None;
+        if self.is_match(vec![TokenType::LeftBrace]) {
+            let mut imported = Vec::new();
+            loop {
+                imported.push(self.consume(TokenType::Identifier, "Expect name to import.".to_string())?.clone());
+                if !self.is_match(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after imported names.".to_string())?;
+            // `from` is only a keyword here, so it stays usable as a variable name
+            if !(self.check(TokenType::Identifier) && self.peek().text == "from") {
+                return Err(self.handle_error(self.peek(), "Expect 'from' after imported names.".to_string()));
+            }
+            self.next_token();
+            names = Some(imported);
         }
-        self.synchronize();
-        Ok(None)
+        let path = self.consume(TokenType::String, "Expect module path.".to_string())?.clone();
+        self.consume(TokenType::Semicolon, "Expect ';' after import.".to_string())?;
+        Ok(Stmt::Import(
+            ImportStmt {
+                keyword,
+                path,
+                names
+            }
+        ))
     }
 
-    fn parse_var_decl(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_export_decl(&self) -> Result<Stmt, (Box<Token>, String)> {
+        let keyword = self.previous().clone();
+        let decl = if self.is_match(vec![TokenType::Var]) {
+            self.parse_var_decl()?
+        } else if self.is_match(vec![TokenType::Fun]) {
+            self.parse_fun_decl("function".to_string())?
+        } else if self.is_match(vec![TokenType::Class]) {
+            self.parse_class_decl()?
+        } else {
+            return Err(self.handle_error(self.peek(), "Expect declaration after 'export'.".to_string()));
+        };
+        Ok(Stmt::Export(
+            ExportStmt {
+                keyword,
+                decl: Box::new(decl)
+            }
+        ))
+    }
+
+    fn parse_var_decl(&self) -> Result<Stmt, (Box<Token>, String)> {
         let identifier = self.consume(TokenType::Identifier, "Expect variable name.".to_string())?;
         let mut initializer = None;
         if self.is_match(vec![TokenType::Equal]) {
@@ -564,9 +894,33 @@ impl Parser {
         ))
     }
 
-    fn parse_fun_decl(&self, kind: String) -> Result<Stmt, (Token, String)> {
+    fn parse_fun_decl(&self, kind: String) -> Result<Stmt, (Box<Token>, String)> {
         let identifier = self.consume(TokenType::Identifier, "Expect ".to_owned()+&kind+" name.")?;
         self.consume(TokenType::LeftParen, "Expect '(' after ".to_owned()+&kind+" name.")?;
+        Ok(Stmt::FunDecl(self.parse_function(identifier.clone(), &kind)?))
+    }
+
+    /// Parameters and body of a function whose `(` has been consumed.
+    fn parse_function(&self, name: Token, kind: &str) -> Result<FunDecl, (Box<Token>, String)> {
+        let params = self.parse_params()?;
+        self.consume(TokenType::LeftBrace, "Expect '{' before ".to_owned()+kind+" name.")?;
+        // a loop around the declaration does not let the body break out of it
+        let loop_depth = self.loop_depth.replace(0);
+        let body = self.parse_block();
+        self.loop_depth.replace(loop_depth);
+        let body = body?;
+        let mut body_stmts = Vec::new();
+        if let Stmt::Block(block) = body {
+            body_stmts = block.stmts;
+        }
+        Ok(FunDecl {
+            name,
+            params,
+            body: body_stmts
+        })
+    }
+
+    fn parse_params(&self) -> Result<Vec<Token>, (Box<Token>, String)> {
         let mut params = Vec::new();
         if !self.check(TokenType::RightParen) {
             loop {
@@ -582,22 +936,10 @@ impl Parser {
             }
         }
         self.consume(TokenType::RightParen, "Expect ')' after parameters.".to_string())?;
-        self.consume(TokenType::LeftBrace, "Expect '{' before ".to_owned()+&kind+" name.")?;
-        let body = self.parse_block()?;
-        let mut body_stmts = Vec::new();
-        if let Stmt::Block(block) = body {
-            body_stmts = block.stmts;
-        }
-        Ok(Stmt::FunDecl(
-            FunDecl { 
-                name: identifier.clone(), 
-                params: params, 
-                body: body_stmts
-            }
-        ))
+        Ok(params)
     }
 
-    fn parse_class_decl(&self) -> Result<Stmt, (Token, String)> {
+    fn parse_class_decl(&self) -> Result<Stmt, (Box<Token>, String)> {
         let identifier = self.consume(TokenType::Identifier, "Expect class name.".to_string())?;
         let superclass = if self.is_match(vec![TokenType::Less]) {
             // extends
@@ -642,6 +984,29 @@ impl Parser {
         self.peek().token_type == token_type
     }
 
+    fn check_next(&self, token_type: TokenType) -> bool {
+        self.tokens.get(*self.current.borrow() + 1).is_some_and(|token| token.token_type == token_type)
+    }
+
+    /// Whether the `(` at the current token opens the parameter list of `(a, b) => expr`.
+    fn is_arrow_function(&self) -> bool {
+        let mut tokens = self.tokens[*self.current.borrow() + 1..].iter();
+        let mut token = tokens.next();
+        if token.is_some_and(|token| token.token_type == TokenType::Identifier) {
+            loop {
+                token = tokens.next();
+                if !token.is_some_and(|token| token.token_type == TokenType::Comma) {
+                    break;
+                }
+                if !tokens.next().is_some_and(|token| token.token_type == TokenType::Identifier) {
+                    return false;
+                }
+            }
+        }
+        token.is_some_and(|token| token.token_type == TokenType::RightParen)
+            && tokens.next().is_some_and(|token| token.token_type == TokenType::Arrow)
+    }
+
     fn next_token(&self) -> &Token {
         if !self.is_end() {
             *self.current.borrow_mut() += 1;
@@ -661,16 +1026,16 @@ impl Parser {
         self.peek().token_type == TokenType::Eof
     }
 
-    fn consume(&self, token_type: TokenType, message: String) -> Result<&Token, (Token, String)> {
+    fn consume(&self, token_type: TokenType, message: String) -> Result<&Token, (Box<Token>, String)> {
         if self.check(token_type) {
             return Ok(self.next_token());
         }
         Err(self.handle_error(self.peek(), message.clone()))
     }
 
-    fn handle_error(&self, token: &Token, message: String) -> (Token, String) {
-        self.error(token.start, token.end, message.clone());
-        (token.clone(), message)
+    fn handle_error(&self, token: &Token, message: String) -> (Box<Token>, String) {
+        self.error(Span::from(token), message.clone());
+        (Box::new(token.clone()), message)
     }
 
     fn synchronize(&self) {
@@ -688,7 +1053,11 @@ impl Parser {
                 TokenType::If |
                 TokenType::While |
                 TokenType::Print |
-                TokenType::Return => {
+                TokenType::Return |
+                TokenType::Import |
+                TokenType::Export |
+                TokenType::Throw |
+                TokenType::Try => {
                     return;
                 }
                 _ => {}
@@ -697,7 +1066,7 @@ impl Parser {
         }
     }
 
-    fn finish_call(&self, callee: Expr) -> Result<Expr, (Token, String)> {
+    fn finish_call(&self, callee: Expr) -> Result<Expr, (Box<Token>, String)> {
         let mut arguments = Vec::new();
         if !self.check(TokenType::RightParen) {
             loop {
@@ -722,9 +1091,15 @@ impl Parser {
 }
 
 impl ErrorReporter for Parser {
-    fn error(&self, start: usize, end: usize, error_content: String) {
-        *self.had_error.borrow_mut() = true;
-        println!("Parser error: {} {} {} {}", self.file_path, start, end, error_content);
-        self.errors.borrow_mut().push((start, end, error_content));
+    fn error(&self, span: Span, error_content: String) {
+        self.diagnostics.borrow_mut().push(Diagnostic::error(Stage::Parser, error_content, span));
+    }
+}
+
+/// The name given to functions without one, located at `token` for diagnostics.
+fn anonymous(token: &Token) -> Token {
+    Token {
+        text: "anonymous".to_string(),
+        ..token.clone()
     }
-}
\ No newline at end of file
+}
@@ -1,25 +1,76 @@
-use std::{cell::RefCell, collections::HashMap, fs, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, collections::{BTreeMap, HashMap}, fs, io::Write, path::{Path, PathBuf}, rc::Rc};
+use glob::{MatchOptions, Pattern};
 use walkdir::WalkDir;
-use crate::{interpreter::Interpreter, parser::Parser, resolver::Resolver, scanner::Scanner};
+use crate::{ast::stmt::ImportStmt, diagnostic::{Diagnostic, ErrorFormat, Span, Stage}, interpreter::Interpreter, module, output::Output, parser::Parser, resolver::Resolver, scanner::Scanner};
+
+/// Result of processing a project, ordered from best to worst so files can be combined with `max`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Status {
+    Ok,
+    RuntimeError,
+    CompileError
+}
 
 pub struct Project {
     pub path: PathBuf,
-    pub files: HashMap<PathBuf, String>
+    // sorted by path, so directories run in the same order every time
+    pub files: BTreeMap<PathBuf, String>,
+    pub error_format: ErrorFormat,
+    pub output: Output,
+    pub error_output: Output,
+    // runs before every other file; either a collected path or one relative to `path`
+    pub entry: Option<PathBuf>,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    // files pulled in by `import` that were not collected
+    imported: BTreeMap<PathBuf, String>,
+    loaded: HashMap<PathBuf, LoadedModule>
+}
+
+struct LoadedModule {
+    status: Status,
+    exports: Vec<String>
 }
 
 impl Project {
     pub fn new(path: PathBuf) -> Self {
         Self { 
             path: path, 
-            files: HashMap::new() 
+            files: BTreeMap::new(),
+            error_format: ErrorFormat::Human,
+            output: Output::Stdout,
+            error_output: Output::Stderr,
+            entry: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            imported: BTreeMap::new(),
+            loaded: HashMap::new()
         }
     }
 
+    /// Only collect files whose path relative to the project directory matches one of the include globs.
+    pub fn include(&mut self, pattern: &str) -> Result<(), String> {
+        self.include.push(Pattern::new(pattern).map_err(|error| format!("Invalid glob '{pattern}': {error}"))?);
+        Ok(())
+    }
+
+    /// Skip files whose path relative to the project directory matches the glob.
+    pub fn exclude(&mut self, pattern: &str) -> Result<(), String> {
+        self.exclude.push(Pattern::new(pattern).map_err(|error| format!("Invalid glob '{pattern}': {error}"))?);
+        Ok(())
+    }
+
+    pub fn entry_file(&self) -> Option<&PathBuf> {
+        let entry = self.entry.as_ref()?;
+        [module::normalize(entry), module::normalize(&self.path.join(entry))].iter()
+            .find_map(|path| self.files.get_key_value(path).map(|(path, _)| path))
+    }
+
     pub fn collect_files(&mut self) {
         if self.is_lox_file(self.path.clone()) {
             // single file
             if let Ok(content) = fs::read_to_string(self.path.clone()) {
-                self.files.insert(self.path.clone(), content);
+                self.files.insert(module::normalize(&self.path), content);
             }
         }
         else if self.path.is_dir() {
@@ -29,8 +80,8 @@ impl Project {
                     continue;
                 }
                 let path = entry.unwrap().into_path();
-                if self.is_lox_file(path.clone()) && let Ok(content) = fs::read_to_string(path.clone()) {
-                    self.files.insert(path, content);
+                if self.is_lox_file(path.clone()) && self.is_selected(&path) && let Ok(content) = fs::read_to_string(path.clone()) {
+                    self.files.insert(module::normalize(&path), content);
                 }
             }
         }
@@ -40,26 +91,144 @@ impl Project {
         path.is_file() && path.extension().is_some() && path.extension().unwrap() == "lox"
     }
 
-    pub fn compile(&mut self) {
-        for (path, content) in &self.files {
-            //println!("file: {}", path.to_string_lossy());
-            let mut scanner = Scanner::new(path.to_string_lossy().to_string(), content.clone());
-            let tokens = scanner.scan_tokens();
-            if *scanner.had_error.borrow() {
-                continue;
-            }
-            let parser = Parser::new(path.to_string_lossy().to_string(), tokens);
-            let stmts = parser.parse();
-            if *parser.had_error.borrow() {
-                continue;
-            }
-            let interpreter = Rc::new(RefCell::new(Interpreter::new()));
-            let mut resolver = Resolver::new(interpreter.clone());
-            resolver.resolve(&stmts);
-            if *resolver.had_error.borrow() {
-                continue;
+    fn is_selected(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.path).unwrap_or(path);
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches_path_with(relative, options)))
+            && !self.exclude.iter().any(|pattern| pattern.matches_path_with(relative, options))
+    }
+
+    /// The entry file first, then the rest sorted by path.
+    fn ordered_files(&self) -> Vec<(&PathBuf, &String)> {
+        let entry = self.entry_file();
+        let mut files: Vec<(&PathBuf, &String)> = entry.iter()
+            .map(|path| (*path, &self.files[*path]))
+            .collect();
+        files.extend(self.files.iter().filter(|(path, _)| Some(*path) != entry));
+        files
+    }
+
+    /// Scans, parses, resolves and runs every collected file.
+    pub fn compile(&mut self) -> Status {
+        self.process(true)
+    }
+
+    /// Like `compile`, but stops after the resolver without executing anything.
+    pub fn check(&mut self) -> Status {
+        self.process(false)
+    }
+
+    fn process(&mut self, execute: bool) -> Status {
+        let interpreter = Rc::new(RefCell::new(Interpreter::with_output(self.output.clone())));
+        let paths: Vec<PathBuf> = self.ordered_files().into_iter().map(|(path, _)| path.clone()).collect();
+        let mut status = Status::Ok;
+        for path in paths {
+            status = status.max(self.load(&interpreter, &path, &mut Vec::new(), execute));
+        }
+        status
+    }
+
+    /// Loads the module at `path` once: its imports first, then the module itself.
+    /// `stack` holds the modules currently being loaded, to detect import cycles.
+    fn load(&mut self, interpreter: &Rc<RefCell<Interpreter>>, path: &Path, stack: &mut Vec<PathBuf>, execute: bool) -> Status {
+        if let Some(loaded) = self.loaded.get(path) {
+            return loaded.status;
+        }
+        let loaded = self.load_file(interpreter, path, stack, execute);
+        let status = loaded.status;
+        self.loaded.insert(path.to_path_buf(), loaded);
+        status
+    }
+
+    fn load_file(&mut self, interpreter: &Rc<RefCell<Interpreter>>, path: &Path, stack: &mut Vec<PathBuf>, execute: bool) -> LoadedModule {
+        let content = self.source(path).unwrap_or_default().to_string();
+        let mut scanner = Scanner::new(path.to_string_lossy().to_string(), content);
+        let tokens = scanner.scan_tokens();
+        self.emit(&scanner.diagnostics.borrow());
+        if *scanner.had_error.borrow() {
+            return LoadedModule { status: Status::CompileError, exports: Vec::new() };
+        }
+        let parse_result = Parser::new(tokens).parse();
+        self.emit(&parse_result.diagnostics);
+        let exports = module::exports(&parse_result.stmts);
+
+        let mut status = Status::Ok;
+        stack.push(path.to_path_buf());
+        for import_stmt in module::imports(&parse_result.stmts) {
+            status = status.max(self.load_import(interpreter, path, import_stmt, stack, execute));
+        }
+        stack.pop();
+
+        // resolve even a partial tree, so semantic errors in the valid parts are reported too
+        let mut resolver = Resolver::new(interpreter.clone());
+        resolver.resolve(&parse_result.stmts);
+        self.emit(&resolver.diagnostics.borrow());
+        if parse_result.has_errors() || *resolver.had_error.borrow() {
+            status = Status::CompileError;
+        }
+        // a module whose imports failed is not run either
+        if execute && status == Status::Ok &&
+           let Err(diagnostic) = interpreter.borrow_mut().interpret_module(path.to_path_buf(), &parse_result.stmts, exports.clone()) {
+            self.emit(&[diagnostic]);
+            status = Status::RuntimeError;
+        }
+        LoadedModule { status, exports }
+    }
+
+    fn load_import(&mut self, interpreter: &Rc<RefCell<Interpreter>>, importer: &Path, import_stmt: &ImportStmt, stack: &mut Vec<PathBuf>, execute: bool) -> Status {
+        let path = module::resolve_import(importer, import_stmt);
+        if let Some(start) = stack.iter().position(|loading| *loading == path) {
+            let cycle: Vec<String> = stack[start..].iter().chain([&path]).map(|path| path.display().to_string()).collect();
+            self.emit(&[Diagnostic::error(Stage::Resolver, format!("Import cycle: {}.", cycle.join(" -> ")), Span::from(&import_stmt.path))]);
+            return Status::CompileError;
+        }
+        if self.source(&path).is_none() {
+            match fs::read_to_string(&path) {
+                Ok(content) => {
+                    self.imported.insert(path.clone(), content);
+                }
+                Err(error) => {
+                    let diagnostic = Diagnostic::error(Stage::Resolver, format!("Cannot read module {}.", import_stmt.path.text), Span::from(&import_stmt.path))
+                        .with_note(format!("{}: {error}", path.display()));
+                    self.emit(&[diagnostic]);
+                    return Status::CompileError;
+                }
             }
-            interpreter.borrow_mut().interpret(&stmts);
+        }
+        let status = self.load(interpreter, &path, stack, execute);
+        if status != Status::Ok {
+            return status;
+        }
+        let exports = &self.loaded[&path].exports;
+        let missing: Vec<Diagnostic> = import_stmt.names.iter().flatten()
+            .filter(|name| !exports.contains(&name.text))
+            .map(|name| Diagnostic::error(Stage::Resolver, format!("Module {} has no export '{}'.", import_stmt.path.text, name.text), Span::from(name)))
+            .collect();
+        self.emit(&missing);
+        if missing.is_empty() {
+            Status::Ok
+        } else {
+            Status::CompileError
+        }
+    }
+
+    fn source(&self, path: &Path) -> Option<&str> {
+        self.files.get(path).or_else(|| self.imported.get(path)).map(|content| content.as_str())
+    }
+
+    fn emit(&self, diagnostics: &[Diagnostic]) {
+        let mut error_output = self.error_output.clone();
+        for diagnostic in diagnostics {
+            let _ = match self.error_format {
+                ErrorFormat::Human => {
+                    let source = self.source(Path::new(&*diagnostic.span.file)).unwrap_or("");
+                    writeln!(error_output, "{}", diagnostic.render(source))
+                }
+                ErrorFormat::Json => writeln!(error_output, "{}", diagnostic.to_json())
+            };
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The generator behind `random()` and `random_int`: xorshift64*, which is fast, small and good
+/// enough for simulations, though not for anything that needs to be unpredictable.
+/// Seeding it with `seed(n)` makes the numbers that follow reproducible.
+pub struct Random {
+    state: u64
+}
+
+impl Random {
+    pub fn new() -> Self {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or(0);
+        Self::with_seed(time)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut random = Self { state: 0 };
+        random.seed(seed);
+        random
+    }
+
+    pub fn seed(&mut self, seed: u64) {
+        // splitmix64 spreads similar seeds apart, and xorshift needs a nonzero state
+        let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        self.state = (z ^ (z >> 31)).max(1);
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
+    }
+}
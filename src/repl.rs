@@ -0,0 +1,162 @@
+use std::{cell::RefCell, env, io::Write, path::PathBuf, rc::Rc};
+use rustyline::{DefaultEditor, error::ReadlineError};
+use crate::{ast::stmt::Stmt, diagnostic::Diagnostic, interpreter::Interpreter, output::Output, parser::{ParseResult, Parser}, resolver::Resolver, scanner::Scanner, token::TokenType};
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = "... ";
+const HISTORY_FILE: &str = ".rust_lox_history";
+
+/// Interactive prompt. The interpreter and resolver live for the whole session,
+/// so variables, functions and classes declared in one input stay visible in the next.
+pub struct Repl {
+    interpreter: Rc<RefCell<Interpreter>>,
+    resolver: Resolver,
+    // diagnostics are rendered here, `print` goes to the interpreter's output
+    error_output: Output,
+    // every input is scanned as its own file `<repl:N>`, kept around for rendering diagnostics
+    sources: Vec<String>
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::with_output(Output::Stdout, Output::Stderr)
+    }
+
+    pub fn with_output(output: Output, error_output: Output) -> Self {
+        let interpreter = Rc::new(RefCell::new(Interpreter::with_output(output)));
+        Self {
+            resolver: Resolver::new(interpreter.clone()),
+            interpreter,
+            error_output,
+            sources: Vec::new()
+        }
+    }
+
+    pub fn run(&mut self) -> rustyline::Result<()> {
+        let mut editor = DefaultEditor::new()?;
+        let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE));
+        if let Some(history) = &history {
+            let _ = editor.load_history(history);
+        }
+
+        let mut buffer = String::new();
+        loop {
+            let prompt = if buffer.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+            match editor.readline(prompt) {
+                Ok(line) => {
+                    buffer.push_str(&line);
+                    buffer.push('\n');
+                    if !is_complete(&buffer) {
+                        continue;
+                    }
+                    let input = std::mem::take(&mut buffer);
+                    if input.trim().is_empty() {
+                        continue;
+                    }
+                    let _ = editor.add_history_entry(input.trim_end());
+                    self.eval(input);
+                }
+                // Ctrl-C drops a half-typed input, or does nothing at an empty prompt
+                Err(ReadlineError::Interrupted) => buffer.clear(),
+                Err(ReadlineError::Eof) => break,
+                Err(error) => return Err(error)
+            }
+        }
+
+        if let Some(history) = &history {
+            let _ = editor.save_history(history);
+        }
+        Ok(())
+    }
+
+    /// Runs one complete input, echoing the value of every bare expression statement.
+    pub fn eval(&mut self, input: String) {
+        let file = format!("<repl:{}>", self.sources.len() + 1);
+        self.sources.push(input.clone());
+
+        let mut scanner = Scanner::new(file.clone(), input.clone());
+        let tokens = scanner.scan_tokens();
+        if *scanner.had_error.borrow() {
+            self.emit(&scanner.diagnostics.borrow());
+            return;
+        }
+        let mut parse_result = Parser::new(tokens).parse();
+        if parse_result.has_errors() && let Some(retry) = self.parse_without_semicolon(&file, &input) {
+            // allow `1 + 2` for `1 + 2;` at the prompt
+            parse_result = retry;
+        }
+        self.emit(&parse_result.diagnostics);
+        if parse_result.has_errors() {
+            return;
+        }
+
+        self.resolver.resolve(&parse_result.stmts);
+        let diagnostics = self.resolver.diagnostics.take();
+        self.emit(&diagnostics);
+        if self.resolver.had_error.replace(false) {
+            return;
+        }
+
+        let mut interpreter = self.interpreter.borrow_mut();
+        for stmt in &parse_result.stmts {
+            let result = if let Stmt::Expr(expr_stmt) = stmt {
+                interpreter.evaluate(&expr_stmt.expr).map(|value| {
+                    let _ = writeln!(interpreter.output, "{value}");
+                })
+            } else {
+                interpreter.interpret(std::slice::from_ref(stmt))
+            };
+            if let Err(diagnostic) = result {
+                drop(interpreter);
+                self.emit(&[diagnostic]);
+                break;
+            }
+        }
+    }
+
+    fn parse_without_semicolon(&self, file: &str, input: &str) -> Option<ParseResult> {
+        let mut scanner = Scanner::new(file.to_string(), format!("{};\n", input.trim_end()));
+        let parse_result = Parser::new(scanner.scan_tokens()).parse();
+        if parse_result.has_errors() {
+            None
+        } else {
+            Some(parse_result)
+        }
+    }
+
+    fn emit(&self, diagnostics: &[Diagnostic]) {
+        let mut error_output = self.error_output.clone();
+        for diagnostic in diagnostics {
+            let source = diagnostic.span.file.strip_prefix("<repl:")
+                .and_then(|rest| rest.strip_suffix('>'))
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| self.sources.get(index - 1))
+                .map_or("", |source| source.as_str());
+            let _ = writeln!(error_output, "{}", diagnostic.render(source));
+        }
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An input is complete once its brackets balance and it has no unterminated string.
+fn is_complete(input: &str) -> bool {
+    let mut scanner = Scanner::new(String::new(), input.to_string());
+    let tokens = scanner.scan_tokens();
+    if scanner.diagnostics.borrow().iter().any(|diagnostic| diagnostic.message == "Unterminated string.") {
+        return false;
+    }
+    let mut depth = 0;
+    for token in tokens {
+        match token.token_type {
+            TokenType::LeftParen | TokenType::LeftBrace | TokenType::LeftSuqareBracket => depth += 1,
+            TokenType::RightParen | TokenType::RightBrace | TokenType::RightSquareBracket => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
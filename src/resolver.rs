@@ -1,18 +1,19 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::{ast::{expr::*, stmt::*}, error::ErrorReporter, interpreter::Interpreter, token::Token, visit::*};
+use crate::{ast::{expr::*, stmt::*}, diagnostic::{Diagnostic, Span, Stage}, error::ErrorReporter, interpreter::Interpreter, token::Token, visit::*};
 
 pub struct Resolver {
     interpreter: Rc<RefCell<Interpreter>>,
     scope_stack: Vec<HashMap<String, bool>>,
     current_function: Rc<RefCell<FunctionType>>,
     current_class: Rc<RefCell<ClassType>>,
-    pub had_error: RefCell<bool>
+    pub had_error: RefCell<bool>,
+    pub diagnostics: RefCell<Vec<Diagnostic>>
 }
 
 impl Visitor for Resolver {
     type R = i8;
-    type E = (Token, String);
+    type E = (Box<Token>, String);
     
     fn visit_block(&mut self, block: &Block) -> Result<Option<Self::R>, Self::E> {
         self.begin_scope();
@@ -21,12 +22,18 @@ impl Visitor for Resolver {
         Ok(None)
     }
 
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Self::R>, Self::E> {
+        self.visit_expr(&for_in_stmt.iterable)?;
+        self.resolve_scope(Some(&for_in_stmt.name), std::slice::from_ref(&for_in_stmt.body))?;
+        Ok(None)
+    }
+
     fn visit_return_stmt(&mut self, return_stmt: &ReturnStmt) -> Result<Option<Self::R>, Self::E> {
         if *self.current_function.borrow() == FunctionType::None {
-            self.error(return_stmt.keyword.start, return_stmt.keyword.end, "Can't return from top-level code.".to_string());
+            self.error(Span::from(&return_stmt.keyword), "Can't return from top-level code.".to_string());
         }
         if return_stmt.value.is_some() && *self.current_function.borrow() == FunctionType::Initializer {
-            self.error(return_stmt.keyword.start, return_stmt.keyword.end, "Can't return a value from initializer.".to_string());
+            self.error(Span::from(&return_stmt.keyword), "Can't return a value from initializer.".to_string());
         }
         self.default_visit_return_stmt(return_stmt)
     }
@@ -51,7 +58,7 @@ impl Visitor for Resolver {
         self.declare(class_decl.name.clone());
         self.define(class_decl.name.clone());
         if let Some(superclass) = &class_decl.superclass && superclass.name == class_decl.name {
-            self.error(superclass.name.start, superclass.name.end, "A class can't inherit from itself.".to_string());        
+            self.error(Span::from(&superclass.name), "A class can't inherit from itself.".to_string());        
         }
         if let Some(superclass) = &class_decl.superclass {
             self.current_class = Rc::new(RefCell::new(ClassType::SubClass));
@@ -77,11 +84,36 @@ impl Visitor for Resolver {
         Ok(None)
     }
 
+    fn visit_import_stmt(&mut self, import_stmt: &ImportStmt) -> Result<Option<Self::R>, Self::E> {
+        if !self.scope_stack.is_empty() {
+            self.error(Span::from(&import_stmt.keyword), "Can only import at the top level.".to_string());
+        }
+        Ok(None)
+    }
+
+    fn visit_export_stmt(&mut self, export_stmt: &ExportStmt) -> Result<Option<Self::R>, Self::E> {
+        if !self.scope_stack.is_empty() {
+            self.error(Span::from(&export_stmt.keyword), "Can only export top-level declarations.".to_string());
+        }
+        self.default_visit_export_stmt(export_stmt)
+    }
+
+    fn visit_try_stmt(&mut self, try_stmt: &TryStmt) -> Result<Option<Self::R>, Self::E> {
+        self.resolve_scope(None, &try_stmt.body)?;
+        if let Some(catch_clause) = &try_stmt.catch_clause {
+            self.resolve_scope(Some(&catch_clause.name), &catch_clause.body)?;
+        }
+        if let Some(finally_body) = &try_stmt.finally_body {
+            self.resolve_scope(None, finally_body)?;
+        }
+        Ok(None)
+    }
+
     fn visit_identifier(&mut self, identifier: &Identifier) -> Result<Option<Self::R>, Self::E> {
         if let Some(scope) = self.scope_stack.last()
            && let Some(state) = scope.get(&identifier.name.text) 
            && *state == false {
-            self.error(identifier.name.start, identifier.name.end, "Can't read local variable in its own initializer.".to_string());
+            self.error(Span::from(&identifier.name), "Can't read local variable in its own initializer.".to_string());
         }
         self.resolve_local(Expr::Identifier(identifier.clone()), &identifier.name);
         self.default_visit_identifier(identifier)?;
@@ -94,9 +126,14 @@ impl Visitor for Resolver {
         Ok(None)
     }
 
+    fn visit_lambda_expr(&mut self, lambda_expr: &LambdaExpr) -> Result<Option<Self::R>, Self::E> {
+        self.resolve_function(&lambda_expr.decl, FunctionType::Function);
+        Ok(None)
+    }
+
     fn visit_this(&mut self, this: &This) -> Result<Option<Self::R>, Self::E> {
         if *self.current_class.borrow() == ClassType::None {
-            self.error(this.keyword.start, this.keyword.end, "Can't use 'this' outside of a class.".to_string());
+            self.error(Span::from(&this.keyword), "Can't use 'this' outside of a class.".to_string());
             return Ok(None);
         }
         self.resolve_local(Expr::This(this.clone()), &this.keyword);
@@ -105,9 +142,9 @@ impl Visitor for Resolver {
 
     fn visit_super(&mut self, super_expr: &Super) -> Result<Option<Self::R>, Self::E> {
         if *self.current_class.borrow() == ClassType::None {
-            self.error(super_expr.keyword.start, super_expr.keyword.end, "Can't use 'super' outside of a class.".to_string());
+            self.error(Span::from(&super_expr.keyword), "Can't use 'super' outside of a class.".to_string());
         } else if *self.current_class.borrow() != ClassType::SubClass {
-            self.error(super_expr.keyword.start, super_expr.keyword.end, "Can't use 'super' in a class with no superclass.".to_string());
+            self.error(Span::from(&super_expr.keyword), "Can't use 'super' in a class with no superclass.".to_string());
         }
         self.resolve_local(Expr::Super(super_expr.clone()), &super_expr.keyword);
         Ok(None)
@@ -121,7 +158,8 @@ impl Resolver {
             scope_stack: Vec::new(),
             current_function: Rc::new(RefCell::new(FunctionType::None)),
             current_class: Rc::new(RefCell::new(ClassType::None)),
-            had_error: RefCell::new(false)
+            had_error: RefCell::new(false),
+            diagnostics: RefCell::new(Vec::new())
         }
     }
 
@@ -145,11 +183,11 @@ impl Resolver {
             if scope.contains_key(&name.text) {
                 err = true;
             } else {
-                scope.insert(name.text, false);
+                scope.insert(name.text.clone(), false);
             }
         }  
         if err {
-            self.error(name.start, name.end, "Already a variable with this name in this scope.".to_string());
+            self.error(Span::from(&name), "Already a variable with this name in this scope.".to_string());
         }      
     }
 
@@ -169,6 +207,21 @@ impl Resolver {
         }
     }
 
+    /// Resolves `stmts` in their own scope, which also holds `name`: the caught error of a `catch`
+    /// clause or the variable of a `for`-`in` loop.
+    fn resolve_scope(&mut self, name: Option<&Token>, stmts: &[Stmt]) -> Result<(), (Box<Token>, String)> {
+        self.begin_scope();
+        if let Some(name) = name {
+            self.declare(name.clone());
+            self.define(name.clone());
+        }
+        for stmt in stmts {
+            self.visit_stmt(stmt)?;
+        }
+        self.end_scope();
+        Ok(())
+    }
+
     fn resolve_function(&mut self, fun_decl: &FunDecl, function_type: FunctionType) {
         let enclosing_function = self.current_function.clone();
         self.current_function = Rc::new(RefCell::new(function_type));
@@ -184,9 +237,9 @@ impl Resolver {
 }
 
 impl ErrorReporter for Resolver {
-    fn error(&self, start: usize, end: usize, error_content: String) {
+    fn error(&self, span: Span, error_content: String) {
         *self.had_error.borrow_mut() = true;
-        println!("Resolve error: {start} {end}: {error_content}");
+        self.diagnostics.borrow_mut().push(Diagnostic::error(Stage::Resolver, error_content, span));
     }
 }
 
@@ -1,35 +1,57 @@
 use std::cell::RefCell;
+use std::rc::Rc;
+use crate::diagnostic::{Diagnostic, Span, Stage};
 use crate::error::ErrorReporter;
 use crate::token::*;
 
 pub struct Scanner {
-    file_path: String,
+    file_path: Rc<str>,
     source: String,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
+    // position of `current`, and of `start` for the token being scanned
+    line: usize,
+    column: usize,
+    start_line: usize,
+    start_column: usize,
+    // unclosed braces inside each `${` being scanned, innermost last
+    interpolations: Vec<usize>,
     pub had_error: RefCell<bool>,
-    errors: RefCell<Vec<(usize, usize, String)>>
+    pub diagnostics: RefCell<Vec<Diagnostic>>
 }
 
 impl Scanner {
     pub fn new(file_path: String, source: String) -> Self {
         Self {
-            file_path: file_path,
+            file_path: Rc::from(file_path),
             source: source,
             tokens: Vec::new(),
             start: 0,
             current: 0,
+            line: 1,
+            column: 1,
+            start_line: 1,
+            start_column: 1,
+            interpolations: Vec::new(),
             had_error: RefCell::new(false),
-            errors: RefCell::new(Vec::new())
+            diagnostics: RefCell::new(Vec::new())
         }
     }
 
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         while !self.is_end() {
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.column;
             self.scan_token();
         }
+        if !self.interpolations.is_empty() {
+            self.error(self.span(), "Unterminated string.".to_string());
+        }
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
         self.add_token(TokenType::Eof, None);
         self.tokens.clone()
     }
@@ -42,17 +64,80 @@ impl Scanner {
             ')' => { self.add_token(TokenType::RightParen, None); }
             '[' => { self.add_token(TokenType::LeftSuqareBracket, None); }
             ']' => { self.add_token(TokenType::RightSquareBracket, None); }
-            '{' => { self.add_token(TokenType::LeftBrace, None); }
-            '}' => { self.add_token(TokenType::RightBrace, None); }
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LeftBrace, None);
+            }
+            '}' => {
+                match self.interpolations.last_mut() {
+                    // the `}` closing `${`; the rest of the string follows
+                    Some(0) => {
+                        self.interpolations.pop();
+                        self.scan_string();
+                    }
+                    Some(depth) => {
+                        *depth -= 1;
+                        self.add_token(TokenType::RightBrace, None);
+                    }
+                    None => self.add_token(TokenType::RightBrace, None)
+                }
+            }
             ',' => { self.add_token(TokenType::Comma, None); }
             '.' => { self.add_token(TokenType::Dot, None); }
-            '-' => { self.add_token(TokenType::Minus, None); }
-            '+' => { self.add_token(TokenType::Plus, None); }
             ';' => { self.add_token(TokenType::Semicolon, None); }
-            '*' => { self.add_token(TokenType::Star, None); }
             '?' => { self.add_token(TokenType::Question, None); }
             ':' => { self.add_token(TokenType::Colon, None); }
 
+            // arithmetic, compound assignment, increment and decrement
+            '-' => {
+                if self.is_match('=') {
+                    self.add_token(TokenType::MinusEqual, None);
+                } else if self.is_match('-') {
+                    self.add_token(TokenType::MinusMinus, None);
+                } else {
+                    self.add_token(TokenType::Minus, None);
+                }
+            }
+            '+' => {
+                if self.is_match('=') {
+                    self.add_token(TokenType::PlusEqual, None);
+                } else if self.is_match('+') {
+                    self.add_token(TokenType::PlusPlus, None);
+                } else {
+                    self.add_token(TokenType::Plus, None);
+                }
+            }
+            '*' => {
+                if self.is_match('=') {
+                    self.add_token(TokenType::StarEqual, None);
+                } else if self.is_match('*') {
+                    self.add_token(TokenType::StarStar, None);
+                } else {
+                    self.add_token(TokenType::Star, None);
+                }
+            }
+            '%' => {
+                if self.is_match('=') {
+                    self.add_token(TokenType::PercentEqual, None);
+                } else {
+                    self.add_token(TokenType::Percent, None);
+                }
+            }
+
+            // bitwise, and `~/` for floor division since `//` starts a comment
+            '&' => { self.add_token(TokenType::Ampersand, None); }
+            '|' => { self.add_token(TokenType::Pipe, None); }
+            '^' => { self.add_token(TokenType::Caret, None); }
+            '~' => {
+                if self.is_match('/') {
+                    self.add_token(TokenType::TildeSlash, None);
+                } else {
+                    self.add_token(TokenType::Tilde, None);
+                }
+            }
+
             // equal
             '!' => {
                 if self.is_match('=') {
@@ -63,12 +148,16 @@ impl Scanner {
             }
             '=' => {if self.is_match('=') {
                     self.add_token(TokenType::EqualEqual, None);
+                } else if self.is_match('>') {
+                    self.add_token(TokenType::Arrow, None);
                 } else {
                     self.add_token(TokenType::Equal, None);
                 }}
             '<' => {
                 if self.is_match('=') {
                     self.add_token(TokenType::LessEqual, None);
+                } else if self.is_match('<') {
+                    self.add_token(TokenType::LessLess, None);
                 } else {
                     self.add_token(TokenType::Less, None);
                 }
@@ -76,6 +165,8 @@ impl Scanner {
             '>' => {
                 if self.is_match('=') {
                     self.add_token(TokenType::GreaterEqual, None);
+                } else if self.is_match('>') {
+                    self.add_token(TokenType::GreaterGreater, None);
                 } else {
                     self.add_token(TokenType::Greater, None);
                 }
@@ -99,6 +190,8 @@ impl Scanner {
                         }
                         self.next_char();
                     }
+                } else if self.is_match('=') {
+                    self.add_token(TokenType::SlashEqual, None);
                 } else {
                     self.add_token(TokenType::Slash, None);
                 }
@@ -121,40 +214,144 @@ impl Scanner {
                     self.scan_identifier();
                 }
                 else {
-                    self.error(self.start, self.current, "Unexpected character.".to_string());
+                    self.error(self.span(), "Unexpected character.".to_string());
                 }
             }
         }
     }
 
+    /// Scans up to the closing `"`, or up to a `${` whose expression is scanned as ordinary tokens
+    /// until the matching `}` continues the string. The parts before each `${` are `Interpolation`
+    /// tokens and the last part is a `String`.
     fn scan_string(&mut self) {
+        let mut value = String::new();
         while self.peek() != '"' && !self.is_end() {
-            self.next_char();
+            let (start, line, column) = (self.current, self.line, self.column);
+            let c = self.next_char();
+            if c == '$' && self.is_match('{') {
+                self.interpolations.push(0);
+                self.add_token(TokenType::Interpolation, Some(Literal::String(value)));
+                return;
+            }
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            match self.next_char() {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                '0' => value.push('\0'),
+                '\\' => value.push('\\'),
+                '"' => value.push('"'),
+                '$' => value.push('$'),
+                'u' => match self.scan_unicode_escape() {
+                    Some(c) => value.push(c),
+                    None => self.error(self.span_from(start, line, column), "Invalid Unicode escape sequence.".to_string())
+                },
+                _ => self.error(self.span_from(start, line, column), "Invalid escape sequence.".to_string())
+            }
         }
         if self.is_end() {
             // error, no " at the end
-            self.error(self.start, self.current-1, "Unterminated string.".to_string());
+            self.error(self.span(), "Unterminated string.".to_string());
             return;
         }
         // close "
         self.next_char();
-        let value = self.source.get(self.start+1..self.current-1);
-        self.add_token(TokenType::String, value.map(|v|Literal::String(v.to_string())));
+        self.add_token(TokenType::String, Some(Literal::String(value)));
+    }
+
+    /// The `{1F600}` after `\u`: one to six hex digits naming a Unicode scalar value.
+    fn scan_unicode_escape(&mut self) -> Option<char> {
+        if !self.is_match('{') {
+            return None;
+        }
+        let digits = self.current;
+        while self.peek().is_ascii_hexdigit() {
+            self.next_char();
+        }
+        let code = u32::from_str_radix(&self.source[digits..self.current], 16).ok();
+        let length = self.current - digits;
+        if !self.is_match('}') || length > 6 {
+            return None;
+        }
+        char::from_u32(code?)
     }
 
+    /// Scans `123`, `1_000`, `1.5`, `1e-9`, `0xFF` or `0b1010`. The literal holds the value in
+    /// decimal without underscores, so that the interpreter can parse it as an `f64`.
     fn scan_number(&mut self) {
-        while self.peek().is_ascii_digit() {
+        let prefix = self.peek();
+        let radix = match (&self.source[self.start..self.current], prefix) {
+            ("0", 'x' | 'X') => 16,
+            ("0", 'b' | 'B') => 2,
+            _ => 10
+        };
+        let literal = if radix == 10 {
+            self.scan_decimal()
+        } else {
             self.next_char();
+            self.scan_radix(radix)
+        };
+
+        // `12abc` or `0b102` is one malformed number rather than a number followed by an identifier
+        if self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+            while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+                self.next_char();
+            }
+            self.error(self.span(), "Invalid number literal.".to_string());
+            return;
+        }
+        match literal {
+            Ok(literal) => self.add_token(TokenType::Number, Some(Literal::Number(literal))),
+            Err(message) => self.error(self.span(), message)
         }
+    }
+
+    fn scan_decimal(&mut self) -> Result<String, String> {
+        self.scan_digits(10);
         // fractional part
         if self.peek() == '.' && self.peek_next().is_ascii_digit() {
             self.next_char();
-            while self.peek().is_ascii_digit() {
+            self.scan_digits(10);
+        }
+        // exponent
+        let sign = matches!(self.peek_next(), '+' | '-');
+        if matches!(self.peek(), 'e' | 'E') && (self.peek_next().is_ascii_digit() || sign) {
+            self.next_char();
+            if sign {
                 self.next_char();
             }
+            if !self.peek().is_ascii_digit() {
+                return Err("Expect digits in exponent.".to_string());
+            }
+            self.scan_digits(10);
         }
+        let text = &self.source[self.start..self.current];
+        check_underscores(text, |c| c.is_ascii_digit())?;
+        Ok(text.replace('_', ""))
+    }
 
-        self.add_token(TokenType::Number, self.source.get(self.start..self.current).map(|v|Literal::Number(v.to_string())));
+    fn scan_radix(&mut self, radix: u32) -> Result<String, String> {
+        let digits = self.current;
+        self.scan_digits(radix);
+        let text = &self.source[digits..self.current];
+        if text.is_empty() {
+            let prefix = &self.source[self.start..digits];
+            return Err(format!("Expect digits after '{prefix}'."));
+        }
+        check_underscores(text, |c| c.is_digit(radix))?;
+        match u64::from_str_radix(&text.replace('_', ""), radix) {
+            Ok(value) => Ok((value as f64).to_string()),
+            Err(_) => Err("Number literal is too large.".to_string())
+        }
+    }
+
+    fn scan_digits(&mut self, radix: u32) {
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            self.next_char();
+        }
     }
 
     fn scan_identifier(&mut self) {
@@ -174,52 +371,86 @@ impl Scanner {
     }
 
     fn next_char(&mut self) -> char {
-        let c = self.source.chars().nth(self.current);
-        self.current += 1;
-        c.unwrap()
+        let c = self.source[self.current..].chars().next().unwrap();
+        self.current += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        c
     }
 
     fn peek(&mut self) -> char {
-        if self.is_end() {
-            return '\0';
-        }
-        let c = self.source.chars().nth(self.current);
-        c.unwrap()
+        self.source[self.current..].chars().next().unwrap_or('\0')
     }
 
     fn peek_next(&mut self) -> char {
-        if self.current+1 >= self.source.len() {
-            return '\0';
-        }
-        let c = self.source.chars().nth(self.current+1);
-        c.unwrap()
+        self.source[self.current..].chars().nth(1).unwrap_or('\0')
     }
 
     fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
         let text = self.source.get(self.start..self.current);
-        self.tokens.push(Token { text: text.unwrap().to_string(), start: self.start, end: self.current-1, token_type: token_type, literal: literal });
+        self.tokens.push(Token {
+            text: text.unwrap().to_string(),
+            file: self.file_path.clone(),
+            start: self.start,
+            end: self.current.saturating_sub(1),
+            line: self.start_line,
+            column: self.start_column,
+            token_type: token_type,
+            literal: literal
+        });
     }
 
     fn is_end(&self) -> bool {
         self.current >= self.source.len()
     }
 
-    fn is_match(&mut self, expected: char) -> bool {
-        if self.is_end() {
-            return false;
+    /// From `start`, at `line` and `column`, to the current position, for errors inside a token.
+    fn span_from(&self, start: usize, line: usize, column: usize) -> Span {
+        Span {
+            file: self.file_path.clone(),
+            start,
+            end: self.current,
+            line,
+            column
         }
-        if self.source.chars().nth(self.current).unwrap() != expected {
+    }
+
+    fn span(&self) -> Span {
+        Span {
+            file: self.file_path.clone(),
+            start: self.start,
+            end: self.current,
+            line: self.start_line,
+            column: self.start_column
+        }
+    }
+
+    fn is_match(&mut self, expected: char) -> bool {
+        if self.is_end() || self.peek() != expected {
             return false;
         }
-        self.current += 1;
+        self.next_char();
         true
     }
 }
 
 impl ErrorReporter for Scanner {
-    fn error(&self, start: usize, end: usize, error_content: String) {
+    fn error(&self, span: Span, error_content: String) {
         *self.had_error.borrow_mut() = true;
-        println!("Scanner error: {} {} {} {}", self.file_path, start, end, error_content);
-        self.errors.borrow_mut().push((start, end, error_content));
+        self.diagnostics.borrow_mut().push(Diagnostic::error(Stage::Scanner, error_content, span));
     }
-}
\ No newline at end of file
+}
+/// Underscores may only separate digits, so `1_000` is fine but not `1__000`, `1_` or `1_.5`.
+fn check_underscores(text: &str, is_digit: impl Fn(char) -> bool) -> Result<(), String> {
+    let chars: Vec<char> = text.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' && !(i > 0 && is_digit(chars[i - 1]) && i + 1 < chars.len() && is_digit(chars[i + 1])) {
+            return Err("Underscores in numbers must be between digits.".to_string());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use std::{fs, path::{Path, PathBuf}, process::Command};
+use walkdir::WalkDir;
+use crate::diagnostic::Stage;
+
+/// Runs `.lox` files and compares what they print against their `// expect` annotations.
+/// Each file is executed as `<executable> run <file>` so crashes cannot take the runner down.
+pub struct Tester {
+    executable: PathBuf,
+    known_failures: Vec<String>
+}
+
+#[derive(Default)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub skipped: usize,
+    pub failures: Vec<(PathBuf, String)>
+}
+
+impl Tester {
+    pub fn new(executable: PathBuf) -> Self {
+        Self {
+            executable,
+            known_failures: Vec::new()
+        }
+    }
+
+    /// Skips the files listed in `path`, one per line relative to the test root.
+    /// A trailing `/` skips a whole directory and `#` starts a comment.
+    pub fn with_known_failures(mut self, path: &Path) -> Self {
+        self.known_failures = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect();
+        self
+    }
+
+    pub fn run(&self, roots: &[PathBuf]) -> TestSummary {
+        let mut summary = TestSummary::default();
+        for path in collect_tests(roots) {
+            if self.is_known_failure(&path) {
+                summary.skipped += 1;
+                continue;
+            }
+            match check(&self.executable, &path) {
+                None => summary.passed += 1,
+                Some(report) => summary.failures.push((path, report))
+            }
+        }
+        summary
+    }
+
+    fn is_known_failure(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        self.known_failures.iter().any(|known| path.ends_with(known.as_str()) || path.contains(&format!("/{}/", known.trim_end_matches('/'))))
+    }
+}
+
+impl TestSummary {
+    pub fn report(&self) -> String {
+        let mut result = String::new();
+        for (path, report) in &self.failures {
+            result.push_str(&format!("{}\n{report}\n", path.display()));
+        }
+        result.push_str(&format!("{} passed, {} failed, {} skipped", self.passed, self.failures.len(), self.skipped));
+        result
+    }
+}
+
+#[derive(Default)]
+struct Expectation {
+    output: Vec<String>,
+    compile_errors: Vec<String>,
+    runtime_error: Option<String>
+}
+
+#[derive(Default)]
+struct Outcome {
+    output: Vec<String>,
+    compile_errors: Vec<String>,
+    runtime_error: Option<String>,
+    // `None` when the process was killed by a signal
+    exit_code: Option<i32>
+}
+
+fn parse_expectation(source: &str) -> Expectation {
+    let mut expectation = Expectation::default();
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        // The annotation may follow code or another comment marker, so try every `//`.
+        for (index, _) in line.match_indices("//") {
+            let comment = line[index+2..].trim();
+            if let Some(output) = comment.strip_prefix("expect: ").or(comment.strip_prefix("Expect: ")) {
+                expectation.output.push(output.to_string());
+            } else if let Some(message) = comment.strip_prefix("expect runtime error: ") {
+                expectation.runtime_error = Some(format!("[line {line_number}] {message}"));
+            } else if let Some(error) = parse_compile_error(comment, line_number) {
+                expectation.compile_errors.push(error);
+            } else {
+                continue;
+            }
+            break;
+        }
+    }
+    expectation
+}
+
+/// Accepts `Error: msg`, `Error at 'x': msg`, `[line N] Error ...` and `[java line N] Error ...`.
+/// Annotations written for clox (`[c line N]`) describe a different implementation and are ignored.
+fn parse_compile_error(comment: &str, mut line_number: usize) -> Option<String> {
+    let comment = if comment.starts_with('[') {
+        let (location, rest) = comment.split_once(']')?;
+        if location.starts_with("[c ") {
+            return None;
+        }
+        line_number = location.rsplit(' ').next()?.parse().ok()?;
+        rest.trim_start()
+    } else {
+        comment
+    };
+    let rest = comment.strip_prefix("Error")?;
+    if let Some(message) = rest.strip_prefix(": ") {
+        return Some(format!("[line {line_number}] {message}"));
+    }
+    if let Some(message) = rest.strip_prefix(" at end: ") {
+        return Some(format!("[line {line_number}] at end: {message}"));
+    }
+    // the token may itself contain `: `, so look for the quote that closes it
+    let (token, message) = rest.strip_prefix(" at '")?.split_once("': ")?;
+    Some(format!("[line {line_number}] at '{token}': {message}"))
+}
+
+/// Where a compile error points, in the form of the annotations: `at 'x'` for the token underlined
+/// at `column`, or `at end` when the error points past the last token of the line.
+fn error_location(source: &str, line_number: usize, column: usize, underline: usize) -> String {
+    let token: String = source.lines().nth(line_number.wrapping_sub(1)).unwrap_or("")
+        .chars().skip(column.saturating_sub(1)).take(underline)
+        .collect();
+    if token.is_empty() {
+        "at end".to_string()
+    } else {
+        format!("at '{token}'")
+    }
+}
+
+fn run(executable: &Path, path: &Path, source: &str) -> Outcome {
+    let output = Command::new(executable)
+        .arg("run")
+        .arg(path)
+        .output()
+        .expect("failed to run rust-lox");
+    let mut outcome = Outcome {
+        output: String::from_utf8_lossy(&output.stdout).lines().map(|line| line.to_string()).collect(),
+        ..Default::default()
+    };
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut lines = stderr.lines();
+    while let Some(line) = lines.next() {
+        let Some((code, message)) = line.strip_prefix("error[").and_then(|rest| rest.split_once("]: ")) else {
+            continue;
+        };
+        let location = lines.next().unwrap_or("");
+        let mut parts = location.rsplit(':');
+        let (column, line_number) = (parts.next().unwrap_or("?"), parts.next().unwrap_or("?"));
+        if code == Stage::Runtime.code() {
+            outcome.runtime_error = Some(format!("[line {line_number}] {message}"));
+        } else if code == Stage::Scanner.code() {
+            outcome.compile_errors.push(format!("[line {line_number}] {message}"));
+        } else {
+            // the snippet after the location underlines the token with `^`
+            let underline = lines.clone().take(3)
+                .find_map(|line| line.split_once('|').map(|(_, rest)| rest.trim()).filter(|rest| rest.starts_with('^')))
+                .map_or(0, |carets| carets.len());
+            let location = match (line_number.parse(), column.parse()) {
+                (Ok(line_number), Ok(column)) => error_location(source, line_number, column, underline),
+                _ => "at ?".to_string()
+            };
+            outcome.compile_errors.push(format!("[line {line_number}] {location}: {message}"));
+        }
+    }
+    outcome.exit_code = output.status.code();
+    outcome
+}
+
+fn diff(label: &str, expected: &[String], actual: &[String]) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let mut result = format!("  {label}:\n");
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => result.push_str(&format!("      {expected}\n")),
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    result.push_str(&format!("    - {expected}\n"));
+                }
+                if let Some(actual) = actual {
+                    result.push_str(&format!("    + {actual}\n"));
+                }
+            }
+        }
+    }
+    Some(result)
+}
+
+fn check(executable: &Path, path: &Path) -> Option<String> {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        // an unreadable file is a broken test, not a passing one
+        Err(error) => return Some(format!("  could not read test: {error}\n"))
+    };
+    let expected = parse_expectation(&source);
+    let actual = run(executable, path, &source);
+
+    let mut expected_errors = expected.compile_errors;
+    let mut actual_errors = actual.compile_errors;
+    expected_errors.sort();
+    actual_errors.sort();
+    // same exit codes as the `run` command: 65 for compile errors, 70 for runtime errors
+    let expected_exit_code = if !expected_errors.is_empty() {
+        65
+    } else if expected.runtime_error.is_some() {
+        70
+    } else {
+        0
+    };
+    let exit_code = actual.exit_code.map_or("killed by a signal".to_string(), |code| code.to_string());
+    let expected_runtime: Vec<String> = expected.runtime_error.into_iter().collect();
+    let actual_runtime: Vec<String> = actual.runtime_error.into_iter().collect();
+
+    let failures: Vec<String> = [
+        diff("output", &expected.output, &actual.output),
+        diff("compile errors", &expected_errors, &actual_errors),
+        diff("runtime error", &expected_runtime, &actual_runtime),
+        diff("exit code", &[expected_exit_code.to_string()], &[exit_code])
+    ].into_iter().flatten().collect();
+    if failures.is_empty() {
+        None
+    } else {
+        Some(failures.concat())
+    }
+}
+
+fn collect_tests(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = roots.iter()
+        .flat_map(|root| WalkDir::new(root).into_iter().flatten())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == "lox"))
+        .collect();
+    paths.sort();
+    paths
+}
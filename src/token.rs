@@ -1,40 +1,53 @@
-pub const KEYWORDS: [(&str, TokenType); 18] = [
+use std::rc::Rc;
+
+pub const KEYWORDS: [(&str, TokenType); 24] = [
    ("and", TokenType::And), ("or", TokenType::Or),
    ("true", TokenType::True), ("false", TokenType::False),
    ("if", TokenType::If), ("else", TokenType::Else), ("for", TokenType::For), ("while", TokenType::While),
    ("print", TokenType::Print), ("return", TokenType::Return), ("super", TokenType::Super), ("this", TokenType::This),
    ("var", TokenType::Var), ("class", TokenType::Class), ("fun", TokenType::Fun), ("nil", TokenType::Nil),
-   ("break", TokenType::Break), ("continue", TokenType::Continue)
+   ("break", TokenType::Break), ("continue", TokenType::Continue),
+   ("import", TokenType::Import), ("export", TokenType::Export),
+   ("throw", TokenType::Throw), ("try", TokenType::Try), ("catch", TokenType::Catch), ("finally", TokenType::Finally)
 ];
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Token {
     pub text: String,
+    pub file: Rc<str>,
     pub start: usize,
     pub end: usize,
+    pub line: usize,
+    pub column: usize,
     pub token_type: TokenType,
     pub literal: Option<Literal>
 }
 
-#[derive(PartialEq, Clone, Eq, Hash)]
+#[derive(PartialEq, Clone, Eq, Hash, Debug)]
 pub enum TokenType {
     LeftParen, RightParen,
     LeftSuqareBracket, RightSquareBracket,
     LeftBrace, RightBrace,
-    Comma, Dot, Minus, Plus, Semicolon, Slash, Star,
+    Comma, Dot, Minus, Plus, Semicolon, Slash, Star, Percent,
+    StarStar, TildeSlash,
+    Ampersand, Pipe, Caret, Tilde, LessLess, GreaterGreater,
+    PlusEqual, MinusEqual, StarEqual, SlashEqual, PercentEqual,
+    PlusPlus, MinusMinus,
     Bang, BangEqual,
     Equal, EqualEqual,
     Greater, GreaterEqual,
     Less, LessEqual,
-    Question, Colon,
-    Identifier, String, Number,
+    Question, Colon, Arrow,
+    Identifier, String, Interpolation, Number,
     And, Or, True, False, If, Else, For, While, Break, Continue,
     Print, Return, Super, This,
     Var, Class, Fun, Nil,
+    Import, Export,
+    Throw, Try, Catch, Finally,
     Eof
 }
 
-#[derive(Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Literal {
     Bool(bool),
     String(String),
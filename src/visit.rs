@@ -25,6 +25,10 @@ pub trait Visitor {
         self.default_visit_while_stmt(while_stmt)
     }
 
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_for_in_stmt(for_in_stmt)
+    }
+
     fn visit_break_stmt(&mut self) -> Result<Option<Self::R>, Self::E> {
         self.default_visit_break_stmt()
     }
@@ -53,6 +57,26 @@ pub trait Visitor {
         self.default_visit_class_decl(class_decl)
     }
 
+    fn visit_import_stmt(&mut self, import_stmt: &ImportStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_import_stmt(import_stmt)
+    }
+
+    fn visit_export_stmt(&mut self, export_stmt: &ExportStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_export_stmt(export_stmt)
+    }
+
+    fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_throw_stmt(throw_stmt)
+    }
+
+    fn visit_try_stmt(&mut self, try_stmt: &TryStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_try_stmt(try_stmt)
+    }
+
+    fn visit_error_stmt(&mut self, error_stmt: &ErrorStmt) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_error_stmt(error_stmt)
+    }
+
     fn visit_expr(&mut self, expr: &Expr) -> Result<Option<Self::R>, Self::E> {
         self.default_visit_expr(expr)
     }
@@ -113,6 +137,10 @@ pub trait Visitor {
         self.default_visit_array_expr(array_expr)
     }
 
+    fn visit_map_expr(&mut self, map_expr: &MapExpr) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_map_expr(map_expr)
+    }
+
     fn visit_subscript_get_expr(&mut self, subscript_get_expr: &SubscriptGetExpr) -> Result<Option<Self::R>, Self::E> {
         self.default_visit_subscript_get_expr(subscript_get_expr)
     }
@@ -121,12 +149,21 @@ pub trait Visitor {
         self.default_visit_subscript_set_expr(subscript_set_expr)
     }
 
+    fn visit_lambda_expr(&mut self, lambda_expr: &LambdaExpr) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_lambda_expr(lambda_expr)
+    }
+
+    fn visit_interpolation_expr(&mut self, interpolation_expr: &InterpolationExpr) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_interpolation_expr(interpolation_expr)
+    }
+
     fn default_visit_stmt(&mut self, stmt: &Stmt) -> Result<Option<Self::R>, Self::E> {
         match stmt {
             Stmt::Expr(expr_stmt) => self.visit_expr_stmt(expr_stmt),
             Stmt::Print(print_stmt) => self.visit_print_stmt(print_stmt),
             Stmt::If(if_stmt) => self.visit_if_stmt(if_stmt),
             Stmt::While(while_stmt) => self.visit_while_stmt(while_stmt),
+            Stmt::ForIn(for_in_stmt) => self.visit_for_in_stmt(for_in_stmt),
             Stmt::Break => self.visit_break_stmt(),
             Stmt::Continue => self.visit_continue_stmt(),
             Stmt::Return(return_stmt) => self.visit_return_stmt(return_stmt),
@@ -134,6 +171,11 @@ pub trait Visitor {
             Stmt::VarDecl(var_decl) => self.visit_var_decl(var_decl),
             Stmt::FunDecl(fun_decl) => self.visit_fun_decl(fun_decl),
             Stmt::ClassDecl(class_decl) => self.visit_class_decl(class_decl),
+            Stmt::Import(import_stmt) => self.visit_import_stmt(import_stmt),
+            Stmt::Export(export_stmt) => self.visit_export_stmt(export_stmt),
+            Stmt::Throw(throw_stmt) => self.visit_throw_stmt(throw_stmt),
+            Stmt::Try(try_stmt) => self.visit_try_stmt(try_stmt),
+            Stmt::Error(error_stmt) => self.visit_error_stmt(error_stmt),
         }
     }
 
@@ -160,6 +202,12 @@ pub trait Visitor {
         Ok(None)
     }
 
+    fn default_visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> Result<Option<Self::R>, Self::E> {
+        self.visit_expr(&for_in_stmt.iterable)?;
+        self.visit_stmt(&for_in_stmt.body)?;
+        Ok(None)
+    }
+
     fn default_visit_break_stmt(&mut self) -> Result<Option<Self::R>, Self::E> {
         Ok(None)
     }
@@ -206,6 +254,39 @@ pub trait Visitor {
         Ok(None)
     }
 
+    fn default_visit_import_stmt(&mut self, _import_stmt: &ImportStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(None)
+    }
+
+    fn default_visit_export_stmt(&mut self, export_stmt: &ExportStmt) -> Result<Option<Self::R>, Self::E> {
+        self.visit_stmt(&export_stmt.decl)
+    }
+
+    fn default_visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt) -> Result<Option<Self::R>, Self::E> {
+        self.visit_expr(&throw_stmt.value)
+    }
+
+    fn default_visit_try_stmt(&mut self, try_stmt: &TryStmt) -> Result<Option<Self::R>, Self::E> {
+        for stmt in &try_stmt.body {
+            self.visit_stmt(stmt)?;
+        }
+        if let Some(catch_clause) = &try_stmt.catch_clause {
+            for stmt in &catch_clause.body {
+                self.visit_stmt(stmt)?;
+            }
+        }
+        if let Some(finally_body) = &try_stmt.finally_body {
+            for stmt in finally_body {
+                self.visit_stmt(stmt)?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn default_visit_error_stmt(&mut self, _error_stmt: &ErrorStmt) -> Result<Option<Self::R>, Self::E> {
+        Ok(None)
+    }
+
     fn default_visit_expr(&mut self, expr: &Expr) -> Result<Option<Self::R>, Self::E> {
         match expr {
             Expr::Binary(binary_expr) => self.visit_binary_expr(binary_expr),
@@ -222,8 +303,11 @@ pub trait Visitor {
             Expr::This(this) => self.visit_this(this),
             Expr::Super(super_expr) => self.visit_super(super_expr),
             Expr::Array(array_expr) => self.visit_array_expr(array_expr),
+            Expr::Map(map_expr) => self.visit_map_expr(map_expr),
             Expr::SubscriptGet(subscript_get_expr) => self.visit_subscript_get_expr(subscript_get_expr),
-            Expr::SubscriptSet(subscript_set_expr) => self.visit_subscript_set_expr(subscript_set_expr)
+            Expr::SubscriptSet(subscript_set_expr) => self.visit_subscript_set_expr(subscript_set_expr),
+            Expr::Lambda(lambda_expr) => self.visit_lambda_expr(lambda_expr),
+            Expr::Interpolation(interpolation_expr) => self.visit_interpolation_expr(interpolation_expr)
         }
     }
 
@@ -299,6 +383,14 @@ pub trait Visitor {
         Ok(None)
     }
 
+    fn default_visit_map_expr(&mut self, map_expr: &MapExpr) -> Result<Option<Self::R>, Self::E> {
+        for (key, value) in &map_expr.entries {
+            self.visit_expr(key)?;
+            self.visit_expr(value)?;
+        }
+        Ok(None)
+    }
+
     fn default_visit_subscript_get_expr(&mut self, subscript_get_expr: &SubscriptGetExpr) -> Result<Option<Self::R>, Self::E> {
         self.visit_expr(&*subscript_get_expr.array)?;
         self.visit_expr(&*subscript_get_expr.index)?;
@@ -311,4 +403,15 @@ pub trait Visitor {
         self.visit_expr(&*subscript_set_expr.value)?;
         Ok(None)
     }
+
+    fn default_visit_lambda_expr(&mut self, lambda_expr: &LambdaExpr) -> Result<Option<Self::R>, Self::E> {
+        self.default_visit_fun_decl(&lambda_expr.decl)
+    }
+
+    fn default_visit_interpolation_expr(&mut self, interpolation_expr: &InterpolationExpr) -> Result<Option<Self::R>, Self::E> {
+        for part in &interpolation_expr.parts {
+            self.visit_expr(part)?;
+        }
+        Ok(None)
+    }
 }
@@ -0,0 +1,124 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn rust_lox(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust-lox"))
+        .args(args)
+        .output()
+        .expect("failed to run rust-lox")
+}
+
+#[test]
+fn json_error_format_emits_one_object_per_diagnostic() {
+    let output = rust_lox(&["--error-format=json", "tests/official/variable/undefined_global.lox"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        vec![concat!(
+            r#"{"file":"tests/official/variable/undefined_global.lox","start":6,"end":16,"line":1,"column":7,"#,
+            r#""stage":"runtime","severity":"error","code":"E0400","message":"Undefined variable 'notDefined'.","notes":[]}"#
+        )]
+    );
+}
+
+#[test]
+fn unknown_error_format_is_a_usage_error() {
+    let output = rust_lox(&["--error-format=xml", "tests/official/empty_file.lox"]);
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn repl_keeps_state_across_inputs_and_echoes_expressions() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-lox"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run rust-lox");
+    child.stdin.take().unwrap()
+        .write_all(b"var a = 1;\nfun add(b) {\n  return a + b;\n}\nadd(2);\nadd(3)\nprint \"done\";\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), vec!["3", "4", "done"]);
+}
+
+#[test]
+fn exit_codes_follow_sysexits() {
+    assert_eq!(rust_lox(&["run", "tests/official/if/else.lox"]).status.code(), Some(0));
+    assert_eq!(rust_lox(&["run", "tests/official/variable/use_false_as_var.lox"]).status.code(), Some(65));
+    assert_eq!(rust_lox(&["run", "tests/official/assignment/undefined.lox"]).status.code(), Some(70));
+    assert_eq!(rust_lox(&["frobnicate", "tests/official/if/else.lox"]).status.code(), Some(64));
+}
+
+#[test]
+fn check_resolves_without_running() {
+    let output = rust_lox(&["check", "tests/official/assignment/undefined.lox"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty() && output.stderr.is_empty());
+}
+
+#[test]
+fn ast_prints_indented_s_expressions() {
+    let output = rust_lox(&["ast", "tests/official/if/else.lox"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("(if true\n  (print \"good\")\n  (print \"bad\"))\n"));
+}
+
+#[test]
+fn directory_runs_entry_first_then_sorted_files() {
+    let stdout_of = |args: &[&str]| String::from_utf8_lossy(&rust_lox(args).stdout).lines().map(str::to_string).collect::<Vec<_>>();
+    assert_eq!(stdout_of(&["run", "tests/fixtures/project"]), vec!["a", "b", "main", "scratch"]);
+    assert_eq!(
+        stdout_of(&["run", "--entry=main.lox", "--exclude=scratch/**", "tests/fixtures/project"]),
+        vec!["main", "a", "b"]
+    );
+    assert_eq!(stdout_of(&["run", "--include=lib/*.lox", "tests/fixtures/project"]), vec!["a", "b"]);
+    assert_eq!(rust_lox(&["run", "--entry=missing.lox", "tests/fixtures/project"]).status.code(), Some(66));
+}
+
+#[test]
+fn import_cycles_are_compile_errors() {
+    let output = rust_lox(&["run", "tests/fixtures/cycle/a.lox"]);
+    assert_eq!(output.status.code(), Some(65));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with(
+        "error[E0300]: Import cycle: tests/fixtures/cycle/a.lox -> tests/fixtures/cycle/b.lox -> tests/fixtures/cycle/a.lox.\n"
+    ));
+}
+
+#[test]
+fn errors_in_nested_calls_stop_the_script_with_every_frame() {
+    let output = rust_lox(&["tests/challenges/call_frames/error_in_nested_call.lox"]);
+    assert_eq!(output.status.code(), Some(70));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let notes: Vec<&str> = stderr.lines().filter_map(|line| line.trim().strip_prefix("= note: ")).collect();
+    assert_eq!(notes, vec![
+        "in b at tests/challenges/call_frames/error_in_nested_call.lox:2",
+        "in a at tests/challenges/call_frames/error_in_nested_call.lox:6",
+        "in <script> at tests/challenges/call_frames/error_in_nested_call.lox:10"
+    ]);
+}
+
+#[test]
+fn runtime_errors_print_a_traceback_innermost_first() {
+    let output = rust_lox(&["tests/challenges/native/len_error_propagates.lox"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let notes: Vec<&str> = stderr.lines().filter_map(|line| line.trim().strip_prefix("= note: ")).collect();
+    assert_eq!(notes, vec![
+        "in len at tests/challenges/native/len_error_propagates.lox:2",
+        "in size at tests/challenges/native/len_error_propagates.lox:2",
+        "in <script> at tests/challenges/native/len_error_propagates.lox:7"
+    ]);
+}
+
+#[test]
+fn caught_errors_leave_no_frames_in_later_tracebacks() {
+    let output = rust_lox(&["tests/challenges/exceptions/traceback_after_catch.lox"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let notes: Vec<&str> = stderr.lines().filter_map(|line| line.trim().strip_prefix("= note: ")).collect();
+    assert_eq!(notes, vec![
+        "in check at tests/challenges/exceptions/traceback_after_catch.lox:12",
+        "in <script> at tests/challenges/exceptions/traceback_after_catch.lox:14"
+    ]);
+}
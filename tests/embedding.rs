@@ -0,0 +1,78 @@
+use std::{cell::Cell, path::PathBuf, rc::Rc};
+use rust_lox::{Lox, interpreter::Value, output::Output, project::{Project, Status}};
+
+#[test]
+fn eval_returns_the_value_of_the_last_expression() {
+    let mut lox = Lox::new();
+    assert_eq!(lox.eval("var a = 1; a + 2").unwrap(), Value::Number(3.0));
+    assert_eq!(lox.eval("var b = a;").unwrap(), Value::Nil);
+    assert_eq!(lox.eval("\"x\" + b").unwrap(), Value::String("x1".to_string()));
+}
+
+#[test]
+fn globals_can_be_read_and_written_from_rust() {
+    let mut lox = Lox::new();
+    lox.set_global("limit", Value::Number(10.0));
+    lox.eval("var over = limit > 5;").unwrap();
+    assert_eq!(lox.get_global("over"), Some(Value::Bool(true)));
+    assert_eq!(lox.get_global("missing"), None);
+}
+
+#[test]
+fn lox_functions_can_be_called_by_name() {
+    let mut lox = Lox::new();
+    lox.eval("fun discount(price, rate) { return price - price * rate; }").unwrap();
+    assert_eq!(lox.call("discount", vec![Value::Number(200.0), Value::Number(0.25)]).unwrap(), Value::Number(150.0));
+
+    let error = lox.call("discount", vec![Value::Number(1.0)]).err().unwrap();
+    assert!(error.diagnostics[0].message.starts_with("Expected"));
+    let error = lox.call("nope", Vec::new()).err().unwrap();
+    assert_eq!(error.diagnostics[0].message, "Undefined variable 'nope'.");
+}
+
+#[test]
+fn host_closures_are_callable_from_lox() {
+    let mut lox = Lox::new();
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    lox.define_function("double", 1, move |arguments| {
+        counter.set(counter.get() + 1);
+        match &arguments[0] {
+            Value::Number(value) => Ok(Value::Number(value * 2.0)),
+            _ => Err("Expected a number.".to_string())
+        }
+    });
+    assert_eq!(lox.eval("double(21)").unwrap(), Value::Number(42.0));
+    assert_eq!(calls.get(), 1);
+
+    let error = lox.eval("fun rule() { return double(\"x\"); }\nrule();").err().unwrap();
+    let diagnostic = &error.diagnostics[0];
+    assert_eq!((diagnostic.message.as_str(), diagnostic.span.line), ("Expected a number.", 1));
+    assert_eq!(diagnostic.notes, vec!["in double at <eval:2>:1", "in rule at <eval:2>:1", "in <script> at <eval:2>:2"]);
+}
+
+#[test]
+fn compile_errors_are_returned_rendered() {
+    let mut lox = Lox::new();
+    let error = lox.eval("var = 1;").err().unwrap();
+    assert_eq!(error.to_string(), "error[E0200]: Expect variable name.\n --> <eval:1>:1:5\n  |\n1 | var = 1;\n  |     ^\n");
+}
+
+#[test]
+fn print_writes_to_the_configured_output() {
+    let output = Output::buffer();
+    let mut lox = Lox::with_output(output.clone());
+    lox.eval("print \"hello\"; print 1 + 2;").unwrap();
+    assert_eq!(output.contents(), "hello\n3\n");
+}
+
+#[test]
+fn projects_write_output_and_diagnostics_to_separate_writers() {
+    let mut project = Project::new(PathBuf::from("tests/challenges/native/len_error_propagates.lox"));
+    project.output = Output::buffer();
+    project.error_output = Output::buffer();
+    project.collect_files();
+    assert_eq!(project.compile(), Status::RuntimeError);
+    assert_eq!(project.output.contents(), "3\n");
+    assert!(project.error_output.contents().starts_with("error[E0400]: Only strings, arrays and maps have length.\n"));
+}
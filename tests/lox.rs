@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+use rust_lox::tester::{TestSummary, Tester};
+
+const TEST_DIRS: [&str; 2] = ["tests/official", "tests/challenges"];
+const KNOWN_FAILURES: &str = "tests/known_failures.txt";
+
+#[test]
+fn lox_files_match_expectations() {
+    let roots: Vec<PathBuf> = TEST_DIRS.iter().map(PathBuf::from).collect();
+    let summary = Tester::new(PathBuf::from(env!("CARGO_BIN_EXE_rust-lox")))
+        .with_known_failures(Path::new(KNOWN_FAILURES))
+        .run(&roots);
+    println!("{} passed, {} failed, {} skipped", summary.passed, summary.failures.len(), summary.skipped);
+    if !summary.failures.is_empty() {
+        panic!("{} lox test(s) failed:\n\n{}", summary.failures.len(), summary.report());
+    }
+}
+
+/// Runs the harness over a scratch directory holding one file, `test.lox`, with `contents`.
+fn run_scratch(name: &str, contents: &[u8]) -> TestSummary {
+    let root = std::env::temp_dir().join(format!("rust-lox-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("test.lox"), contents).unwrap();
+    let summary = Tester::new(PathBuf::from(env!("CARGO_BIN_EXE_rust-lox"))).run(std::slice::from_ref(&root));
+    std::fs::remove_dir_all(&root).unwrap();
+    summary
+}
+
+#[test]
+fn unreadable_files_are_reported_as_failures() {
+    // not UTF-8, so the file cannot be read as source
+    let summary = run_scratch("unreadable", &[0xff, 0xfe]);
+    assert_eq!(summary.passed, 0);
+    assert_eq!(summary.failures.len(), 1);
+    assert!(summary.failures[0].1.starts_with("  could not read test: "));
+}
+
+#[test]
+fn compile_errors_must_point_at_the_annotated_token() {
+    assert_eq!(run_scratch("right-token", b"var = 1; // Error at '=': Expect variable name.\n").passed, 1);
+    let summary = run_scratch("wrong-token", b"var = 1; // Error at '1': Expect variable name.\n");
+    assert_eq!(summary.failures.len(), 1);
+    assert!(summary.failures[0].1.contains("+ [line 1] at '=': Expect variable name."));
+    assert_eq!(run_scratch("at-end", b"{ // Error at end: Expect '}' after block.").passed, 1);
+}